@@ -0,0 +1,301 @@
+use card::{Card, Suit};
+use hand::Hand;
+use calculations::{equity_against, whole_deck};
+
+use std::collections::HashMap;
+use std::old_io::{BufferedReader, File, IoResult};
+use std::rand::{thread_rng, Rng};
+
+static SUITS: [Suit; 4] = [Suit::Diamond, Suit::Club, Suit::Heart, Suit::Spade];
+static BUCKET_MAP_HEADER: &'static str = "buckets 1";
+
+/// How a hand's equity against one random hand is spread once the rest of the board comes, over
+/// `samples` run-outs, as the share of run-outs landing in each of `bins` equal slices of 0 to 1.
+/// On the river there's nothing left to come, so all of it lands in one bin.
+pub fn equity_histogram(hole: &Hand, board: &Hand, bins: usize, samples: usize) -> Vec<f32> {
+    let mut rng = thread_rng();
+    let mut histogram = range(0, bins).map(|_| 0.0).collect::<Vec<f32>>();
+    let to_come = 5 - board.cards().len();
+    let runouts = if to_come == 0 { 1 } else { samples };
+    let deck = whole_deck() - hole.clone() - board.clone();
+    for _ in range(0, runouts) {
+        let mut cards = deck.cards();
+        rng.shuffle(cards.as_mut_slice());
+        let full_board = board.clone() + Hand::new(cards.into_iter().take(to_come).collect());
+        let (equity, _) = equity_against(hole.clone(), &vec![], full_board, Hand::new(vec![]), 1);
+        let bin = (equity * bins as f32) as usize;
+        histogram[if bin < bins { bin } else { bins - 1 }] += 1.0 / runouts as f32;
+    }
+    histogram
+}
+
+/// Earth mover's distance between two histograms over the same bins: how much mass has to move
+/// how many bins to turn one into the other.
+pub fn earth_movers_distance(first: &Vec<f32>, second: &Vec<f32>) -> f32 {
+    let (_, distance) = first.iter().zip(second.iter()).fold((0.0, 0.0), |(carried, distance), (a, b)| {
+        let carried = carried + a - b;
+        (carried, distance + if carried < 0.0 { -carried } else { carried })
+    });
+    distance
+}
+
+/// k-means over histograms by earth mover's distance: the centers start spread out, each from the
+/// histogram farthest from the ones picked so far, then every histogram goes to its nearest center and
+/// each center moves to the average of its histograms, `iterations` times.
+/// Returns the centers and which one each histogram went to.
+pub fn cluster(histograms: &Vec<Vec<f32>>, clusters: usize, iterations: usize) -> (Vec<Vec<f32>>, Vec<usize>) {
+    if histograms.is_empty() {
+        return (vec![], vec![]);
+    }
+    let mut centers = vec![histograms[0].clone()];
+    while centers.len() < clusters && centers.len() < histograms.len() {
+        let farthest = range(0, histograms.len()).fold(0, |farthest, index| {
+            match distance_to_nearest(&histograms[index], &centers) > distance_to_nearest(&histograms[farthest], &centers) {
+                true => index,
+                false => farthest,
+            }
+        });
+        centers.push(histograms[farthest].clone());
+    }
+    let mut assignments = histograms.iter().map(|histogram| nearest(histogram, &centers)).collect::<Vec<usize>>();
+    for _ in range(0, iterations) {
+        for (center_index, center) in centers.iter_mut().enumerate() {
+            let members = histograms.iter().zip(assignments.iter())
+                          .filter(|&(_, &assigned)| assigned == center_index)
+                          .map(|(histogram, _)| histogram)
+                          .collect::<Vec<&Vec<f32>>>();
+            if members.is_empty() {
+                continue;
+            }
+            *center = range(0, center.len())
+                      .map(|bin| members.iter().fold(0.0, |total, member| total + member[bin]) / members.len() as f32)
+                      .collect();
+        }
+        let moved = histograms.iter().map(|histogram| nearest(histogram, &centers)).collect::<Vec<usize>>();
+        if moved == assignments {
+            break;
+        }
+        assignments = moved;
+    }
+    (centers, assignments)
+}
+
+fn nearest(histogram: &Vec<f32>, centers: &Vec<Vec<f32>>) -> usize {
+    range(0, centers.len()).fold(0, |nearest, index| {
+        match earth_movers_distance(histogram, &centers[index]) < earth_movers_distance(histogram, &centers[nearest]) {
+            true => index,
+            false => nearest,
+        }
+    })
+}
+
+fn distance_to_nearest(histogram: &Vec<f32>, centers: &Vec<Vec<f32>>) -> f32 {
+    earth_movers_distance(histogram, &centers[nearest(histogram, centers)])
+}
+
+/// One name for every hole cards and board that only differ by which suit is which, like "AHKH|2H7D9C".
+/// The suits are renamed whichever way writes the cards out first in order. Jokers have no suit to rename,
+/// so hands with them have no name.
+pub fn canonical(hole: &Hand, board: &Hand) -> Option<String> {
+    let mut best: Option<String> = None;
+    for renaming in suit_renamings().iter() {
+        let written = match (renamed(hole, renaming), renamed(board, renaming)) {
+            (Some(hole), Some(board)) => format!("{}|{}", hole, board),
+            _ => return None,
+        };
+        if best.as_ref().map_or(true, |best| written < *best) {
+            best = Some(written);
+        }
+    }
+    best
+}
+
+fn suit_renamings() -> Vec<Vec<Suit>> {
+    let mut renamings = vec![];
+    for &a in SUITS.iter() {
+        for &b in SUITS.iter().filter(|&&b| b != a) {
+            for &c in SUITS.iter().filter(|&&c| c != a && c != b) {
+                for &d in SUITS.iter().filter(|&&d| d != a && d != b && d != c) {
+                    renamings.push(vec![a, b, c, d]);
+                }
+            }
+        }
+    }
+    renamings
+}
+
+fn renamed(hand: &Hand, renaming: &Vec<Suit>) -> Option<String> {
+    let mut cards = vec![];
+    for card in hand.cards().iter() {
+        match SUITS.iter().position(|suit| suit == card.suit()) {
+            Some(suit) => cards.push(Card::new(*card.value(), renaming[suit])),
+            None => return None,
+        }
+    }
+    cards.sort_by(|left, right| right.cmp(left));
+    Some(cards.iter().map(|card| format!("{}{}", card.value().to_char(), card.suit().to_char())).collect::<Vec<String>>().concat())
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum BucketMapErr {
+    Unreadable,
+    BadHeader,
+    BadLine(String),
+}
+
+/// Which bucket each canonical hand falls in, for the streets it was built from.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BucketMap {
+    buckets: HashMap<String, usize>,
+    bucket_count: usize,
+}
+
+impl BucketMap {
+    /// Clusters `situations` (hole cards and board) into `buckets` buckets by their equity histograms.
+    /// Situations that are the same up to suits are only worked out once, and ones with jokers are left out.
+    pub fn build(situations: &Vec<(Hand, Hand)>, buckets: usize, bins: usize, samples: usize, iterations: usize) -> BucketMap {
+        // Each name and where its histogram is.
+        let mut names = HashMap::new();
+        let mut histograms = vec![];
+        for &(ref hole, ref board) in situations.iter() {
+            match canonical(hole, board) {
+                Some(ref name) if !names.contains_key(name) => {
+                    names.insert(name.clone(), histograms.len());
+                    histograms.push(equity_histogram(hole, board, bins, samples));
+                },
+                _ => (),
+            }
+        }
+        let (centers, assignments) = cluster(&histograms, buckets, iterations);
+        BucketMap {
+            buckets: names.into_iter().map(|(name, index)| (name, assignments[index])).collect(),
+            bucket_count: centers.len(),
+        }
+    }
+
+    pub fn bucket_count(&self) -> usize {
+        self.bucket_count
+    }
+
+    /// The bucket for `hole` on `board`, if the map has that situation.
+    pub fn bucket(&self, hole: &Hand, board: &Hand) -> Option<usize> {
+        canonical(hole, board).and_then(|name| self.buckets.get(&name).map(|&bucket| bucket))
+    }
+
+    /// A header line with the number of buckets, then a line per canonical hand: its name, a tab, and its bucket.
+    pub fn write<W: Writer>(&self, writer: &mut W) -> IoResult<()> {
+        try!(writer.write_line(format!("{} {}", BUCKET_MAP_HEADER, self.bucket_count).as_slice()));
+        let mut names = self.buckets.keys().collect::<Vec<&String>>();
+        names.sort();
+        for name in names.into_iter() {
+            try!(writer.write_line(format!("{}\t{}", name, self.buckets.get(name).unwrap()).as_slice()));
+        }
+        Ok(())
+    }
+
+    pub fn read<B: Buffer>(reader: &mut B) -> Result<BucketMap, BucketMapErr> {
+        let mut lines = reader.lines();
+        let bucket_count = match lines.next() {
+            Some(Ok(header)) => {
+                let header = header.trim();
+                match header.starts_with(BUCKET_MAP_HEADER) {
+                    true => match header[BUCKET_MAP_HEADER.len()..].trim().parse::<usize>() {
+                        Ok(bucket_count) => bucket_count,
+                        Err(_) => return Err(BucketMapErr::BadHeader),
+                    },
+                    false => return Err(BucketMapErr::BadHeader),
+                }
+            },
+            Some(Err(_)) => return Err(BucketMapErr::Unreadable),
+            None => return Err(BucketMapErr::BadHeader),
+        };
+        let mut buckets = HashMap::new();
+        for line in lines {
+            let line = match line {
+                Ok(line) => line.trim().to_string(),
+                Err(_) => return Err(BucketMapErr::Unreadable),
+            };
+            if line.is_empty() {
+                continue;
+            }
+            let parts = line.split('\t').collect::<Vec<&str>>();
+            match (parts.len(), parts.last().and_then(|bucket| bucket.parse::<usize>().ok())) {
+                (2, Some(bucket)) if bucket < bucket_count => { buckets.insert(parts[0].to_string(), bucket); },
+                _ => return Err(BucketMapErr::BadLine(line.clone())),
+            }
+        }
+        Ok(BucketMap { buckets: buckets, bucket_count: bucket_count })
+    }
+
+    pub fn save(&self, path: &Path) -> IoResult<()> {
+        let mut file = try!(File::create(path));
+        self.write(&mut file)
+    }
+
+    pub fn load(path: &Path) -> Result<BucketMap, BucketMapErr> {
+        match File::open(path) {
+            Ok(file) => BucketMap::read(&mut BufferedReader::new(file)),
+            Err(_) => Err(BucketMapErr::Unreadable),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonical, earth_movers_distance, cluster, equity_histogram, BucketMap, BucketMapErr};
+    use hand::Hand;
+    use std::num::Float;
+    use std::old_io::BufReader;
+
+    fn hand(s: &str) -> Hand {
+        s.parse().ok().unwrap()
+    }
+
+    #[test] fn hands_the_same_but_for_suits_share_a_name() {
+        assert_eq!(canonical(&hand("AH KH"), &hand("2H 7D 9C")), canonical(&hand("KS AS"), &hand("9D 2S 7C")));
+        assert!(canonical(&hand("AH KH"), &hand("2H 7D 9C")) != canonical(&hand("AH KH"), &hand("2D 7H 9C")));
+        assert!(canonical(&hand("AH KD"), &hand("2H 7D 9C")) != canonical(&hand("AH KH"), &hand("2H 7D 9C")));
+    }
+
+    #[test] fn distance_grows_with_how_far_mass_moves() {
+        assert_eq!(earth_movers_distance(&vec![1.0, 0.0, 0.0], &vec![1.0, 0.0, 0.0]), 0.0);
+        assert_eq!(earth_movers_distance(&vec![1.0, 0.0, 0.0], &vec![0.0, 1.0, 0.0]), 1.0);
+        assert_eq!(earth_movers_distance(&vec![1.0, 0.0, 0.0], &vec![0.0, 0.0, 1.0]), 2.0);
+    }
+
+    #[test] fn clusters_split_far_apart_histograms() {
+        let histograms = vec![vec![1.0, 0.0, 0.0, 0.0], vec![0.0, 0.0, 0.0, 1.0], vec![0.9, 0.1, 0.0, 0.0], vec![0.0, 0.0, 0.2, 0.8]];
+        let (centers, assignments) = cluster(&histograms, 2, 10);
+        assert_eq!(centers.len(), 2);
+        assert_eq!(assignments[0], assignments[2]);
+        assert_eq!(assignments[1], assignments[3]);
+        assert!(assignments[0] != assignments[1]);
+    }
+
+    #[test] fn river_histograms_are_a_single_bin() {
+        let histogram = equity_histogram(&hand("AH AD"), &hand("AS AC 2D 7H 9S"), 10, 50);
+        assert!((histogram.iter().fold(0.0, |total, share| total + *share) - 1.0).abs() < 0.001);
+        assert_eq!(histogram[9], 1.0);
+    }
+
+    #[test] fn bucket_maps_survive_being_saved_and_loaded() {
+        let board = hand("AS KC 7D 4H 2S");
+        let situations = vec![(hand("AH AD"), board.clone()), (hand("9C 8D"), board.clone()), (hand("KH KD"), board.clone())];
+        let map = BucketMap::build(&situations, 2, 10, 1, 5);
+        assert_eq!(map.bucket(&hand("AH AD"), &board), map.bucket(&hand("KH KD"), &board));
+        let mut bytes = vec![];
+        map.write(&mut bytes).ok().unwrap();
+        let loaded = BucketMap::read(&mut BufReader::new(bytes.as_slice())).ok().unwrap();
+        assert_eq!(loaded, map);
+        assert_eq!(BucketMap::read(&mut BufReader::new(b"buckets 1 2\nAHAD|AS\t7\n")), Err(BucketMapErr::BadLine("AHAD|AS\t7".to_string())));
+    }
+
+    #[test] fn hands_with_jokers_have_no_bucket() {
+        let board = hand("AS KC 7D 4H 2S");
+        let situations = vec![(hand("AH AD"), board.clone()), (hand("AH XX"), board.clone())];
+        let map = BucketMap::build(&situations, 2, 10, 1, 5);
+        assert_eq!(canonical(&hand("AH XX"), &board), None);
+        assert_eq!(map.bucket(&hand("AH XX"), &board), None);
+        assert_eq!(map.bucket(&hand("AD AC"), &board), Some(0));
+    }
+}
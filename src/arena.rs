@@ -0,0 +1,347 @@
+use card::Card;
+use hand::Hand;
+use calculations::{sampled_equity_against, mean_confidence_interval, whole_deck};
+use history::{HandHistory, ActionRecord};
+use preflop::PreflopMatrix;
+use range::HandClass;
+use table::{Table, HandState, Street, Action};
+
+use std::iter::repeat;
+use std::rand::{Rng, SeedableRng, XorShiftRng};
+
+/// Everything a player at the table can see when it's their turn.
+#[derive(Debug, Clone)]
+pub struct Observation {
+    pub seat: usize,
+    pub button: usize,
+    pub hole_cards: Hand,
+    pub board: Hand,
+    pub street: Street,
+    pub actions: Vec<ActionRecord>,
+    pub stacks: Vec<u32>,
+    pub folded: Vec<bool>,
+    pub pot: u32,
+    pub to_call: u32,
+    pub big_blind: u32,
+    /// Bets and raises are given at their minimum size; `raise_range` has the largest.
+    pub legal_actions: Vec<Action>,
+    pub raise_range: Option<(u32, u32)>,
+}
+
+/// What the player to act in `hand` can see.
+pub fn observe(hand: &HandState, big_blind: u32) -> Observation {
+    let seat = hand.to_act().unwrap();
+    let seats = hand.stacks().len();
+    Observation {
+        seat: seat,
+        button: hand.button(),
+        hole_cards: hand.hole_cards(seat),
+        board: hand.board(),
+        street: hand.street(),
+        actions: hand.actions().clone(),
+        stacks: hand.stacks(),
+        folded: range(0, seats).map(|seat| hand.has_folded(seat)).collect(),
+        pot: hand.pot(),
+        to_call: hand.to_call(),
+        big_blind: big_blind,
+        legal_actions: hand.legal_actions(),
+        raise_range: hand.raise_range(),
+    }
+}
+
+/// A player that decides for itself: a bot, or a person behind some interface.
+pub trait Agent {
+    fn name(&self) -> String;
+    /// Anything that isn't legal is taken as a check, or a fold when there's a bet to call.
+    fn act(&mut self, observation: &Observation) -> Action;
+    /// Shown every hand once it's over, from the seat the agent had.
+    fn hand_over(&mut self, _history: &HandHistory, _seat: usize) {}
+}
+
+/// Checks when it can and calls when it can't.
+pub struct AlwaysCall;
+
+impl Agent for AlwaysCall {
+    fn name(&self) -> String {
+        "Always Call".to_string()
+    }
+
+    fn act(&mut self, observation: &Observation) -> Action {
+        match observation.to_call {
+            0 => Action::Check,
+            _ => Action::Call,
+        }
+    }
+}
+
+/// Picks one of the legal actions at random, betting or raising a random amount when it does.
+pub struct RandomAgent {
+    rng: XorShiftRng,
+}
+
+impl RandomAgent {
+    pub fn new(seed: [u32; 4]) -> RandomAgent {
+        RandomAgent { rng: SeedableRng::from_seed(seed) }
+    }
+}
+
+impl Agent for RandomAgent {
+    fn name(&self) -> String {
+        "Random".to_string()
+    }
+
+    fn act(&mut self, observation: &Observation) -> Action {
+        let action = observation.legal_actions[self.rng.gen_range(0, observation.legal_actions.len())];
+        match (action, observation.raise_range) {
+            (Action::Bet(_), Some((least, most))) => Action::Bet(self.rng.gen_range(least, most + 1)),
+            (Action::Raise(_), Some((least, most))) => Action::Raise(self.rng.gen_range(least, most + 1)),
+            (action, _) => action,
+        }
+    }
+}
+
+/// Raises the minimum with at least `raise_above` equity against the players still in, calls when the
+/// pot odds are good enough, and folds otherwise. Heads-up before the flop it looks its equity up in the
+/// exact preflop table; otherwise it deals boards from its own seeded generator, so runs can be repeated.
+pub struct RaiseByEquity {
+    raise_above: f32,
+    rng: XorShiftRng,
+    samples: usize,
+    preflop: PreflopMatrix,
+}
+
+impl RaiseByEquity {
+    pub fn new(raise_above: f32, seed: [u32; 4]) -> RaiseByEquity {
+        RaiseByEquity { raise_above: raise_above, rng: SeedableRng::from_seed(seed), samples: 200, preflop: PreflopMatrix::exact() }
+    }
+
+    /// How many boards to deal for each decision it can't look up, 200 by default.
+    pub fn with_samples(mut self, samples: usize) -> RaiseByEquity {
+        self.samples = samples;
+        self
+    }
+
+    fn equity(&mut self, observation: &Observation) -> f32 {
+        let opponents = range(0, observation.folded.len())
+                        .filter(|&seat| seat != observation.seat && !observation.folded[seat])
+                        .count();
+        let hole_cards = observation.hole_cards.cards();
+        let preflop_class = match (observation.street, opponents, hole_cards.len()) {
+            (Street::Preflop, 1, 2) => HandClass::of(&hole_cards[0], &hole_cards[1]),
+            _ => None,
+        };
+        if let Some(class) = preflop_class {
+            // Against a random hand: the average over every class, weighted by how many combos it has.
+            let (equity, combos) = HandClass::all().into_iter().fold((0.0, 0.0), |(equity, combos), villain| {
+                let count = villain.combo_count() as f32;
+                (equity + count * self.preflop.lookup(class, villain), combos + count)
+            });
+            return equity / combos;
+        }
+        let (equity, _) = sampled_equity_against(&mut self.rng, self.samples, observation.hole_cards.clone(), &vec![],
+                                                 observation.board.clone(), Hand::new(vec![]), opponents);
+        equity
+    }
+}
+
+impl Agent for RaiseByEquity {
+    fn name(&self) -> String {
+        format!("Raise By Equity ({:.2})", self.raise_above)
+    }
+
+    fn act(&mut self, observation: &Observation) -> Action {
+        let equity = self.equity(observation);
+        let raise = observation.legal_actions.iter().find(|action| match **action {
+            Action::Bet(_) | Action::Raise(_) => true,
+            _ => false,
+        });
+        match raise {
+            Some(&raise) if equity >= self.raise_above => return raise,
+            _ => (),
+        }
+        let pot_after_call = (observation.pot + observation.to_call) as f32;
+        match observation.to_call {
+            0 => Action::Check,
+            to_call if equity * pot_after_call >= to_call as f32 => Action::Call,
+            _ => Action::Fold,
+        }
+    }
+}
+
+/// Plays agents against each other, hand after hand. Every hand starts everyone with the same stack,
+/// the decks come from a seeded generator so a run can be repeated, and the agents move round a seat
+/// each hand so they all take their turn on the button.
+pub struct Arena {
+    agents: Vec<Box<Agent>>,
+    stack: u32,
+    small_blind: u32,
+    big_blind: u32,
+    rng: XorShiftRng,
+}
+
+impl Arena {
+    pub fn new(agents: Vec<Box<Agent>>, stack: u32, small_blind: u32, big_blind: u32) -> Arena {
+        Arena { agents: agents, stack: stack, small_blind: small_blind, big_blind: big_blind, rng: SeedableRng::from_seed([1, 2, 3, 4]) }
+    }
+
+    pub fn with_seed(mut self, seed: [u32; 4]) -> Arena {
+        self.rng = SeedableRng::from_seed(seed);
+        self
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.agents.iter().map(|agent| agent.name()).collect()
+    }
+
+    pub fn big_blind(&self) -> u32 {
+        self.big_blind
+    }
+
+    /// The arena's generator, for anything else a run needs to come out the same from the same seed.
+    pub fn rng(&mut self) -> &mut XorShiftRng {
+        &mut self.rng
+    }
+
+    /// A freshly shuffled deck from the arena's generator.
+    pub fn shuffled_deck(&mut self) -> Vec<Card> {
+        let mut deck = whole_deck().cards();
+        self.rng.shuffle(deck.as_mut_slice());
+        deck
+    }
+
+    /// Plays one hand from `deck` with `seating[seat]` being the agent in each seat, the button in seat 0.
+    /// Returns the hand and what each agent won or lost in it, in big blinds.
+    pub fn play_hand(&mut self, seating: &Vec<usize>, deck: Vec<Card>) -> (HandHistory, Vec<f32>) {
+        let table = Table::new(repeat(self.stack).take(seating.len()).collect(), self.small_blind, self.big_blind);
+        let mut hand = HandState::new(&table, deck).ok().expect("A hand needs two players with chips");
+        while let Some(seat) = hand.to_act() {
+            let action = self.agents[seating[seat]].act(&observe(&hand, self.big_blind));
+            if hand.act(action).is_err() {
+                let fallback = hand.legal_actions()[0];
+                hand.act(fallback).ok().unwrap();
+            }
+        }
+        let history = hand.history();
+        let mut results = repeat(0.0).take(self.agents.len()).collect::<Vec<f32>>();
+        for (seat, &stack) in hand.stacks().iter().enumerate() {
+            self.agents[seating[seat]].hand_over(&history, seat);
+            results[seating[seat]] = (stack as f32 - self.stack as f32) / self.big_blind as f32;
+        }
+        (history, results)
+    }
+
+    pub fn run(&mut self, hands: usize) -> ArenaReport {
+        let players = self.agents.len();
+        let mut results = vec![];
+        for hand in range(0, hands) {
+            let seating = range(0, players).map(|seat| (seat + hand) % players).collect();
+            let deck = self.shuffled_deck();
+            let (_, won) = self.play_hand(&seating, deck);
+            results.push(won);
+        }
+        ArenaReport { names: self.names(), results: results }
+    }
+}
+
+/// What each agent won in each hand of a run, in big blinds.
+#[derive(Debug, Clone)]
+pub struct ArenaReport {
+    names: Vec<String>,
+    results: Vec<Vec<f32>>,
+}
+
+impl ArenaReport {
+    pub fn new(names: Vec<String>, results: Vec<Vec<f32>>) -> ArenaReport {
+        ArenaReport { names: names, results: results }
+    }
+
+    pub fn names(&self) -> &Vec<String> {
+        &self.names
+    }
+
+    pub fn hands(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn results(&self, agent: usize) -> Vec<f32> {
+        self.results.iter().map(|hand| hand[agent]).collect()
+    }
+
+    /// The agent's win rate in big blinds per hundred hands, and the confidence interval around it.
+    pub fn bb_per_100(&self, agent: usize) -> (f32, f32) {
+        let (mean, interval) = mean_confidence_interval(&self.results(agent));
+        (mean * 100.0, interval * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Agent, AlwaysCall, RandomAgent, RaiseByEquity, Arena, Observation, observe};
+    use table::{Table, HandState, Action, stacked_deck};
+
+    /// Folds whenever it's asked to act.
+    struct Folder;
+
+    impl Agent for Folder {
+        fn name(&self) -> String {
+            "Folder".to_string()
+        }
+
+        fn act(&mut self, _: &Observation) -> Action {
+            Action::Fold
+        }
+    }
+
+    fn random_game(seed: [u32; 4]) -> Arena {
+        Arena::new(vec![Box::new(RandomAgent::new([5, 6, 7, 8])) as Box<Agent>, Box::new(AlwaysCall) as Box<Agent>], 200, 1, 2)
+            .with_seed(seed)
+    }
+
+    #[test] fn runs_with_the_same_seed_come_out_the_same() {
+        let first = random_game([9, 9, 9, 9]).run(100);
+        let second = random_game([9, 9, 9, 9]).run(100);
+        assert_eq!(first.results(0), second.results(0));
+        assert_eq!(first.names(), &vec!["Random".to_string(), "Always Call".to_string()]);
+    }
+
+    #[test] fn whatever_one_agent_wins_the_other_loses() {
+        let report = random_game([1, 1, 2, 3]).run(100);
+        for (first, second) in report.results(0).iter().zip(report.results(1).iter()) {
+            assert_eq!(*first, -*second);
+        }
+        let (first, first_interval) = report.bb_per_100(0);
+        let (second, _) = report.bb_per_100(1);
+        assert_eq!(first, -second);
+        assert!(first_interval > 0.0);
+    }
+
+    #[test] fn agents_take_turns_on_the_button() {
+        let mut arena = Arena::new(vec![Box::new(AlwaysCall) as Box<Agent>, Box::new(Folder) as Box<Agent>], 100, 1, 2);
+        let report = arena.run(100);
+        // Heads-up the button is the small blind, and folding it away costs half a big blind.
+        assert_eq!(report.results(1).iter().filter(|&&result| result == -0.5).count(), 50);
+    }
+
+    #[test] fn equity_raises_good_hands_and_calls_cheap_ones() {
+        let first_to_act = |deck: &str| {
+            let hand = HandState::new(&Table::new(vec![100, 100], 1, 2), stacked_deck(deck)).ok().unwrap();
+            observe(&hand, 2)
+        };
+        let mut agent = RaiseByEquity::new(0.6, [1, 2, 3, 4]);
+        // Aces have about 85% against a random hand, and 7-2 offsuit about 35%, which is plenty to complete the small blind.
+        let aces = first_to_act("KD AS KC AH 2C 7D 8H 9C 3S JD 4C 5H");
+        assert!(match agent.act(&aces) { Action::Raise(_) => true, _ => false });
+        let rags = first_to_act("AS 7D AH 2C 3C 9D 8H 9C 3S JD 4C 5H");
+        assert_eq!(agent.act(&rags), Action::Call);
+    }
+
+    #[test] fn runs_with_equity_agents_come_out_the_same() {
+        let game = || {
+            Arena::new(vec![Box::new(RaiseByEquity::new(0.6, [1, 2, 3, 4]).with_samples(50)) as Box<Agent>,
+                            Box::new(RaiseByEquity::new(0.7, [5, 6, 7, 8]).with_samples(50)) as Box<Agent>,
+                            Box::new(AlwaysCall) as Box<Agent>], 200, 1, 2)
+                .with_seed([4, 3, 2, 1])
+        };
+        assert_eq!(game().run(20).results(0), game().run(20).results(0));
+    }
+}
@@ -0,0 +1,148 @@
+use card::Card;
+use hand::Hand;
+use low::ace_low_rank;
+use calculations::{whole_deck, confidence_interval, SAMPLES};
+
+use std::cmp::Ordering;
+use std::rand::{thread_rng, Rng};
+
+/// The best set of cards with no two sharing a value or a suit. Aces are low.
+/// More cards beat fewer, then lower cards beat higher ones. A better badugi compares greater. Jokers don't play.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Badugi {
+    ranks: Vec<usize>,
+}
+
+impl Badugi {
+    pub fn new(hand: &Hand) -> Badugi {
+        let cards = hand.natural_cards();
+        range(1, 1 << cards.len())
+            .map(|mask: usize| {
+                cards.iter()
+                     .enumerate()
+                     .filter(|&(index, _)| mask & (1 << index) != 0)
+                     .map(|(_, &card)| card)
+                     .collect::<Vec<Card>>()
+            })
+            .filter(|cards| all_different(cards))
+            .map(|cards| {
+                let mut ranks = cards.iter().filter_map(|card| ace_low_rank(card.value())).collect::<Vec<usize>>();
+                ranks.sort_by(|left, right| right.cmp(left));
+                Badugi { ranks: ranks }
+            })
+            .max()
+            .unwrap_or(Badugi { ranks: vec![] })
+    }
+
+    pub fn cards_played(&self) -> usize {
+        self.ranks.len()
+    }
+}
+
+fn all_different(cards: &Vec<Card>) -> bool {
+    cards.iter().enumerate().all(|(index, card)| {
+        cards.iter().skip(index + 1).all(|other| card.value() != other.value() && card.suit() != other.suit())
+    })
+}
+
+impl PartialOrd for Badugi {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Badugi {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.ranks.len().cmp(&other.ranks.len()) {
+            Ordering::Equal => other.ranks.cmp(&self.ranks),
+            order => order,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BadugiErr {
+    TooManyKept,
+}
+
+/// The chance of drawing to `kept` and beating every opponent, drawing back up to four cards.
+/// The `discarded` cards are dead. Opponents are dealt four cards and stand pat.
+pub fn chance_of_winning(kept: Hand, discarded: Hand, other_players: usize) -> Result<(f32, f32), BadugiErr> {
+    if kept.cards().len() > 4 {
+        return Err(BadugiErr::TooManyKept);
+    }
+    let mut rng = thread_rng();
+    let remaining_deck = whole_deck() - kept.clone() - discarded;
+    let draws = 4 - kept.cards().len();
+    let results = range(0, SAMPLES)
+                  .map(|_| {
+                      let mut deck_cards = remaining_deck.cards();
+                      rng.shuffle(deck_cards.as_mut_slice());
+                      let my_badugi = Badugi::new(&(kept.clone() + Hand::new(deck_cards.iter().take(draws).map(|&card| card).collect())));
+                      range(0, other_players)
+                      .map(|player_index| {
+                          Hand::new(deck_cards
+                                    .iter()
+                                    .skip(draws + 4 * player_index)
+                                    .take(4)
+                                    .map(|&card| card)
+                                    .collect())
+                      })
+                      .all(|opponents_hand| my_badugi > Badugi::new(&opponents_hand))
+                  }).collect::<Vec<bool>>();
+    let total: f32 = results.iter().fold(0.0, |sum, _| sum + 1.0);
+    let wins: f32 = results.iter().filter(|result| **result).fold(0.0, |sum, _| sum + 1.0);
+    Ok((wins / total, confidence_interval(wins, total)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Badugi, BadugiErr, chance_of_winning};
+    use hand::Hand;
+
+    fn parse_badugi(s: &str) -> Badugi {
+        Badugi::new(&s.parse::<Hand>().ok().unwrap())
+    }
+
+    #[test] fn four_different_suits_and_values_play_all_four() {
+        assert_eq!(parse_badugi("AS 2H 3D 4C").cards_played(), 4);
+    }
+
+    #[test] fn paired_values_play_only_one() {
+        assert_eq!(parse_badugi("AS AH 3D 4C").cards_played(), 3);
+    }
+
+    #[test] fn shared_suits_play_only_one() {
+        assert_eq!(parse_badugi("AS 2S 3S 4C").cards_played(), 2);
+    }
+
+    #[test] fn the_lowest_badugi_is_best() {
+        assert!(parse_badugi("AS 2H 3D 4C") > parse_badugi("AS 2H 3D 5C"));
+    }
+
+    #[test] fn badugis_compare_from_the_highest_card() {
+        assert!(parse_badugi("8S 5H 4D 3C") > parse_badugi("8H 6D 3S 2C"));
+    }
+
+    #[test] fn any_four_card_badugi_beats_a_three_card_hand() {
+        assert!(parse_badugi("KS QH JD 0C") > parse_badugi("AS 2S 3D 4H"));
+    }
+
+    #[test] fn three_card_hands_drop_the_highest_clashing_card() {
+        assert!(parse_badugi("AS 2H 3D 3C") == parse_badugi("AS 2H 3D"));
+    }
+
+    #[test] fn at_most_four_cards_can_be_kept() {
+        match chance_of_winning("AS 2H 3D 4C 5S".parse().ok().unwrap(), Hand::new(vec![]), 1) {
+            Err(BadugiErr::TooManyKept) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test] fn the_best_badugi_almost_always_wins() {
+        let (wheel, _) = chance_of_winning("AS 2H 3D 4C".parse().ok().unwrap(), Hand::new(vec![]), 1).ok().unwrap();
+        let (kings, _) = chance_of_winning("KS QH JD 0C".parse().ok().unwrap(), Hand::new(vec![]), 1).ok().unwrap();
+        assert!(wheel > 0.99);
+        assert!(kings < wheel);
+    }
+}
@@ -0,0 +1,112 @@
+extern crate poker;
+
+use poker::calculations::whole_deck;
+use poker::protocol::{match_state, parse_action, is_reply_to};
+use poker::table::{Table, HandState, Action};
+
+use std::iter::repeat;
+use std::old_io::{TcpListener, TcpStream, BufferedStream, Listener, Acceptor};
+use std::os;
+use std::rand::{thread_rng, Rng};
+
+/// Hosts a no-limit hold'em match between bots on localhost.
+/// Usage: server <port> <players> <hands> [stack] [small blind] [big blind] [milliseconds to act]
+/// Every player is sent the state whenever it changes, one line at a time, and replies to states where
+/// it's their turn with the state and their action on the end. Replies to any other state, like ones
+/// that turned up too late, are skipped. Anything that doesn't arrive in time or doesn't make sense is
+/// taken as a check, or a fold when there's a bet to call.
+fn main() {
+    let args = os::args();
+    if args.len() < 4 {
+        println!("Usage: {} <port> <players> <hands> [stack] [small blind] [big blind] [milliseconds to act]", args[0]);
+        return;
+    }
+    let number = |index: usize, default: u64| match args.get(index) {
+        Some(arg) => arg.parse::<u64>().ok().expect("Arguments have to be numbers"),
+        None => default,
+    };
+    let port = number(1, 0) as u16;
+    let players = number(2, 0) as usize;
+    let hands = number(3, 0) as usize;
+    let stack = number(4, 200) as u32;
+    let small_blind = number(5, 1) as u32;
+    let big_blind = number(6, 2) as u32;
+    let time_limit = number(7, 1000);
+    if players < 2 {
+        println!("A match needs at least two players");
+        return;
+    }
+    if stack == 0 {
+        println!("Players need chips to play");
+        return;
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", port)).ok().expect("Couldn't listen on that port");
+    let mut acceptor = listener.listen().ok().expect("Couldn't listen on that port");
+    println!("Waiting for {} players on port {}", players, port);
+    let mut connections = vec![];
+    while connections.len() < players {
+        match acceptor.accept() {
+            Ok(stream) => {
+                connections.push(BufferedStream::new(stream));
+                println!("Player {} connected", connections.len() - 1);
+            },
+            Err(error) => println!("Couldn't accept a player: {}", error),
+        }
+    }
+
+    let mut totals = repeat(0i64).take(players).collect::<Vec<i64>>();
+    for hand_number in range(0, hands) {
+        // The players move round a seat each hand so they all take their turn on the button.
+        let seating = range(0, players).map(|seat| (seat + hand_number) % players).collect::<Vec<usize>>();
+        let table = Table::new(repeat(stack).take(players).collect(), small_blind, big_blind);
+        let mut deck = whole_deck().cards();
+        thread_rng().shuffle(deck.as_mut_slice());
+        let mut hand = HandState::new(&table, deck).ok().expect("A hand needs two players with chips");
+
+        send_states(&mut connections, &seating, &hand, hand_number);
+        while let Some(seat) = hand.to_act() {
+            let state = match_state(&hand, seat, hand_number);
+            let action = read_action(&mut connections[seating[seat]], state.as_slice(), &hand, time_limit);
+            let acted = match action {
+                Some(action) => hand.act(action).is_ok(),
+                None => false,
+            };
+            if !acted {
+                let fallback = hand.legal_actions()[0];
+                hand.act(fallback).ok().unwrap();
+            }
+            send_states(&mut connections, &seating, &hand, hand_number);
+        }
+
+        for (seat, &chips) in hand.stacks().iter().enumerate() {
+            totals[seating[seat]] += chips as i64 - stack as i64;
+        }
+    }
+
+    for (player, total) in totals.iter().enumerate() {
+        println!("Player {}: {} chips", player, total);
+    }
+}
+
+/// The player's reply to `state`, skipping any lines left over from earlier states. `None` if no reply
+/// comes within `time_limit` milliseconds or it doesn't make sense.
+fn read_action(connection: &mut BufferedStream<TcpStream>, state: &str, hand: &HandState, time_limit: u64) -> Option<Action> {
+    connection.get_mut().set_read_timeout(Some(time_limit));
+    loop {
+        match connection.read_line() {
+            Ok(ref reply) if is_reply_to(reply.as_slice(), state) => return parse_action(reply.as_slice(), state, hand),
+            Ok(_) => (),
+            Err(_) => return None,
+        }
+    }
+}
+
+/// Sends everyone the state from their own seat. A player who can't be reached just misses it.
+fn send_states(connections: &mut Vec<BufferedStream<TcpStream>>, seating: &Vec<usize>, hand: &HandState, hand_number: usize) {
+    for (seat, &player) in seating.iter().enumerate() {
+        let connection = &mut connections[player];
+        let _ = connection.write_line(match_state(hand, seat, hand_number).as_slice());
+        let _ = connection.flush();
+    }
+}
@@ -1,3 +1,4 @@
+use card::Card;
 use hand::Hand;
 
 static WHOLE_DECK: [&'static str; 52] = [
@@ -7,41 +8,47 @@ static WHOLE_DECK: [&'static str; 52] = [
     "AS", "2S", "3S", "4S", "5S", "6S", "7S", "8S", "9S", "0S", "JS", "QS", "KS",
 ];
 
-static SAMPLES: usize = 1000;
+pub static SAMPLES: usize = 1000;
 
+use std::iter::repeat;
 use std::rand::{thread_rng, Rng};
 
 extern crate core;
 use self::core::num::ToPrimitive;
 
+extern crate rust_combinatorics;
+use self::rust_combinatorics::combinatorics::binomial::Chooseable;
+
 #[cfg(not(test))]
 pub fn chance_of_winning(my_pocket: Hand, community_cards: Hand, other_players: usize) -> (f32, f32) {
+    chance_of_winning_against(my_pocket, community_cards, Hand::new(vec![]), other_players, 2, |dealt: &Hand, _: &Hand| dealt.clone())
+}
+
+/// Like `chance_of_winning`, with `dead_cards` out of the deck and opponents dealt `opponent_hole_cards` each.
+/// `keep` picks the cards each opponent plays from what they were dealt, seeing only `community_cards`,
+/// so nobody chooses with hindsight of the cards still to come.
+pub fn chance_of_winning_against<F>(my_pocket: Hand, community_cards: Hand, dead_cards: Hand, other_players: usize,
+                                    opponent_hole_cards: usize, keep: F) -> (f32, f32) where F: Fn(&Hand, &Hand) -> Hand {
     let mut rng = thread_rng();
-    let deck = WHOLE_DECK.iter()
-                    .fold(String::new(), |string, card| string + " " + card)
-                    .trim()
-                    .parse::<Hand>()
-                    .ok()
-                    .unwrap();
-    let remaining_deck = deck - my_pocket.clone() - community_cards.clone();
+    let remaining_deck = whole_deck() - my_pocket.clone() - community_cards.clone() - dead_cards;
+    let community_cards_needed = 5 - community_cards.cards().len();
     let results = range(0, SAMPLES)
                   .map(|_| {
                       let mut deck_cards = remaining_deck.cards();
                       let mut cards = deck_cards.as_mut_slice();
                       rng.shuffle(cards);
-                      let community_cards_needed = 5 - community_cards.cards().len();
-                      let community_cards = community_cards.clone() + Hand::new(cards.iter().take(community_cards_needed).map(|&card| card).collect());
+                      let board = community_cards.clone() + Hand::new(cards.iter().take(community_cards_needed).map(|&card| card).collect());
                       range(0, other_players)
                       .map(|player_index| {
                           Hand::new(cards
                                     .iter()
-                                    .skip(community_cards_needed + 2 * player_index)
-                                    .take(2)
+                                    .skip(community_cards_needed + opponent_hole_cards * player_index)
+                                    .take(opponent_hole_cards)
                                     .map(|&card| card)
                                     .collect())
                       })
-                      .all(|opponents_pocket| {
-                          (my_pocket.clone() + community_cards.clone()) > (opponents_pocket + community_cards.clone())
+                      .all(|dealt| {
+                          (my_pocket.clone() + board.clone()) > keep(&dealt, &community_cards) + board.clone()
                       })
                   }).collect::<Vec<bool>>();
     let total: f32 = results.iter().fold(0.0, |sum, _| sum + 1.0);
@@ -49,15 +56,121 @@ pub fn chance_of_winning(my_pocket: Hand, community_cards: Hand, other_players:
     (wins / total, confidence_interval(wins, total))
 }
 
+/// `my_pocket`'s share of the pot against opponents holding `opponents`, plus `unknown_opponents` dealt
+/// two random cards each, with the rest of the board dealt at random. Ties split the pot.
+pub fn equity_against(my_pocket: Hand, opponents: &Vec<Hand>, community_cards: Hand, dead_cards: Hand, unknown_opponents: usize) -> (f32, f32) {
+    sampled_equity_against(&mut thread_rng(), SAMPLES, my_pocket, opponents, community_cards, dead_cards, unknown_opponents)
+}
+
+/// Like `equity_against`, dealing `samples` times from `rng`, so the same generator gives the same answer.
+pub fn sampled_equity_against<R: Rng>(rng: &mut R, samples: usize, my_pocket: Hand, opponents: &Vec<Hand>, community_cards: Hand,
+                                      dead_cards: Hand, unknown_opponents: usize) -> (f32, f32) {
+    let remaining_deck = opponents.iter().fold(whole_deck() - my_pocket.clone() - community_cards.clone() - dead_cards,
+                                               |deck, pocket| deck - pocket.clone());
+    let shares = range(0, samples)
+                 .map(|_| {
+                     let mut deck_cards = remaining_deck.cards();
+                     rng.shuffle(deck_cards.as_mut_slice());
+                     let community_cards_needed = 5 - community_cards.cards().len();
+                     let community_cards = community_cards.clone() + Hand::new(deck_cards.iter().take(community_cards_needed).map(|&card| card).collect());
+                     let mine = my_pocket.clone() + community_cards.clone();
+                     let theirs = opponents.iter()
+                                  .map(|pocket| pocket.clone() + community_cards.clone())
+                                  .chain(range(0, unknown_opponents).map(|player_index| {
+                                      Hand::new(deck_cards
+                                                .iter()
+                                                .skip(community_cards_needed + 2 * player_index)
+                                                .take(2)
+                                                .map(|&card| card)
+                                                .collect()) + community_cards.clone()
+                                  }))
+                                  .collect::<Vec<Hand>>();
+                     match theirs.iter().any(|hand| *hand > mine) {
+                         true => 0.0,
+                         false => 1.0 / (1 + theirs.iter().filter(|hand| **hand == mine).count()) as f32,
+                     }
+                 }).collect::<Vec<f32>>();
+    let total: f32 = shares.iter().fold(0.0, |sum, _| sum + 1.0);
+    let won: f32 = shares.iter().fold(0.0, |sum, share| sum + *share);
+    (won / total, confidence_interval(won, total))
+}
+
+/// Each of `pockets`' share of the pot with `community_cards` out, over every way the rest of the board
+/// can come. That's only quick with a card or two to come.
+pub fn enumerated_equities(pockets: &Vec<Hand>, community_cards: Hand) -> Vec<f32> {
+    let community_cards_needed = 5 - community_cards.cards().len();
+    let deck = pockets.iter().fold(whole_deck() - community_cards.clone(), |deck, pocket| deck - pocket.clone()).cards();
+    match community_cards_needed {
+        0 => average_shares(pockets, &community_cards, Some(vec![]).into_iter()),
+        _ => average_shares(pockets, &community_cards, deck.choose(community_cards_needed)),
+    }
+}
+
+/// Like `enumerated_equities`, over `samples` boards dealt from `rng`. Everyone's share is taken from the
+/// same boards, so the shares add up to one.
+pub fn sampled_equities<R: Rng>(rng: &mut R, samples: usize, pockets: &Vec<Hand>, community_cards: Hand) -> Vec<f32> {
+    let community_cards_needed = 5 - community_cards.cards().len();
+    let deck = pockets.iter().fold(whole_deck() - community_cards.clone(), |deck, pocket| deck - pocket.clone()).cards();
+    let boards = range(0, samples).map(|_| {
+        let mut deck_cards = deck.clone();
+        rng.shuffle(deck_cards.as_mut_slice());
+        deck_cards.into_iter().take(community_cards_needed).collect::<Vec<Card>>()
+    });
+    average_shares(pockets, &community_cards, boards)
+}
+
+/// Each pocket's average share of the pot over the boards `community_cards` and each of `rests` make.
+fn average_shares<I: Iterator<Item=Vec<Card>>>(pockets: &Vec<Hand>, community_cards: &Hand, rests: I) -> Vec<f32> {
+    let mut shares = repeat(0.0).take(pockets.len()).collect::<Vec<f32>>();
+    let mut boards = 0;
+    for rest in rests {
+        let board = community_cards.clone() + Hand::new(rest);
+        let hands = pockets.iter().map(|pocket| pocket.clone() + board.clone()).collect::<Vec<Hand>>();
+        let best = hands.iter().max().unwrap().clone();
+        let winners = hands.iter().filter(|hand| **hand == best).count();
+        for (share, hand) in shares.iter_mut().zip(hands.iter()) {
+            if *hand == best {
+                *share += 1.0 / winners as f32;
+            }
+        }
+        boards += 1;
+    }
+    shares.iter().map(|share| share / boards as f32).collect()
+}
+
+pub fn whole_deck() -> Hand {
+    WHOLE_DECK.iter()
+        .fold(String::new(), |string, card| string + " " + card)
+        .trim()
+        .parse::<Hand>()
+        .ok()
+        .unwrap()
+}
+
 static CONFIDENCE: f32 = 0.95;
 use std::num::Float;
 
-fn confidence_interval(successes: f32, observed: f32) -> f32 {
+pub fn confidence_interval(successes: f32, observed: f32) -> f32 {
     let z = percent_std_normal(1.0 - (1.0 - CONFIDENCE) / 2.0);
     let p = successes / observed;
     z * (1.0 / observed * p * (1.0 - p)).sqrt()
 }
 
+/// The mean of `values` and the half-width of the confidence interval around it.
+/// With fewer than two values there's no spread to go on: the interval is infinite, and the mean is 0 with none.
+pub fn mean_confidence_interval(values: &Vec<f32>) -> (f32, f32) {
+    match values.len() {
+        0 => return (0.0, Float::infinity()),
+        1 => return (values[0], Float::infinity()),
+        _ => (),
+    }
+    let observed = values.len() as f32;
+    let mean = values.iter().fold(0.0, |sum, value| sum + *value) / observed;
+    let variance = values.iter().fold(0.0, |sum, value| sum + (*value - mean).powi(2)) / (observed - 1.0);
+    let z = percent_std_normal(1.0 - (1.0 - CONFIDENCE) / 2.0);
+    (mean, z * (variance / observed).sqrt())
+}
+
 use self::core::f32::consts::{SQRT2, FRAC_2_SQRTPI};
 
 fn percent_std_normal(x: f32) -> f32 {
@@ -93,8 +206,11 @@ fn erf(x: f32) -> f32 {
 
 #[cfg(test)]
 mod tests {
-    use super::{confidence_interval};
+    use super::{confidence_interval, mean_confidence_interval, whole_deck, chance_of_winning_against, equity_against, sampled_equity_against,
+                enumerated_equities, sampled_equities};
+    use hand::Hand;
     use std::num::Float;
+    use std::rand::{SeedableRng, XorShiftRng};
 
     #[test] fn confidence_interval_works_for_exact_successes_and_observed() {
         assert_eq!(confidence_interval(1.0, 1.0), 0.0);
@@ -103,4 +219,68 @@ mod tests {
     #[test] fn confidence_interval_works_for_arbitrary_successes_and_observed() {
         assert!((confidence_interval(670.0, 1000.0) - 0.0291).abs() < 0.0005);
     }
+
+    #[test] fn opponents_play_the_cards_they_keep() {
+        let board = "KD 9H 7S 8C JD".parse::<Hand>().ok().unwrap();
+        let keep_rags = |_: &Hand, seen: &Hand| {
+            assert_eq!(seen.cards().len(), 5);
+            "2C 3D".parse::<Hand>().ok().unwrap()
+        };
+        let (chance, _) = chance_of_winning_against("AS AH".parse().ok().unwrap(), board, Hand::new(vec![]), 2, 3, keep_rags);
+        assert_eq!(chance, 1.0);
+    }
+
+    #[test] fn equity_against_known_hands_on_a_full_board_is_certain() {
+        let board = "2C 7D 9H JS 4C".parse::<Hand>().ok().unwrap();
+        let kings = vec!["KS KD".parse::<Hand>().ok().unwrap()];
+        assert_eq!(equity_against("AS AH".parse().ok().unwrap(), &kings, board.clone(), Hand::new(vec![]), 0), (1.0, 0.0));
+        assert_eq!(equity_against("QS QD".parse().ok().unwrap(), &kings, board, Hand::new(vec![]), 0), (0.0, 0.0));
+    }
+
+    #[test] fn equity_against_splits_ties() {
+        let board = "AS KS QS JS 0S".parse::<Hand>().ok().unwrap();
+        let opponents = vec!["2C 3C".parse::<Hand>().ok().unwrap(), "4D 5D".parse::<Hand>().ok().unwrap()];
+        let (equity, _) = equity_against("6H 7H".parse().ok().unwrap(), &opponents, board, Hand::new(vec![]), 0);
+        assert!((equity - 1.0 / 3.0).abs() < 0.0001);
+    }
+
+    #[test] fn mean_confidence_interval_shrinks_with_the_spread() {
+        let (mean, interval) = mean_confidence_interval(&vec![1.0, -1.0, 1.0, -1.0]);
+        assert_eq!(mean, 0.0);
+        assert!((interval - 1.96 * (4.0f32 / 3.0 / 4.0).sqrt()).abs() < 0.01);
+        assert_eq!(mean_confidence_interval(&vec![2.0, 2.0, 2.0]), (2.0, 0.0));
+    }
+
+    #[test] fn one_value_has_no_interval_to_speak_of() {
+        assert_eq!(mean_confidence_interval(&vec![]), (0.0, Float::infinity()));
+        assert_eq!(mean_confidence_interval(&vec![3.0]), (3.0, Float::infinity()));
+    }
+
+    #[test] fn equity_from_the_same_seed_comes_out_the_same() {
+        let sample = |seed: [u32; 4]| {
+            let mut rng: XorShiftRng = SeedableRng::from_seed(seed);
+            sampled_equity_against(&mut rng, 50, "AS KS".parse().ok().unwrap(), &vec![], Hand::new(vec![]), Hand::new(vec![]), 2)
+        };
+        assert_eq!(sample([1, 2, 3, 4]), sample([1, 2, 3, 4]));
+    }
+
+    #[test] fn enumerated_equities_split_every_board_between_the_hands() {
+        let pockets = vec!["AS AH".parse::<Hand>().ok().unwrap(), "KS KD".parse::<Hand>().ok().unwrap()];
+        assert_eq!(enumerated_equities(&pockets, "2C 7D 9H JS 4C".parse().ok().unwrap()), vec![1.0, 0.0]);
+        // Only the two kings left in the deck save the kings on the river.
+        let equities = enumerated_equities(&pockets, "2C 7D 9H JS".parse().ok().unwrap());
+        assert!((equities[1] - 2.0 / 44.0).abs() < 0.0001);
+        assert!((equities[0] + equities[1] - 1.0).abs() < 0.0001);
+    }
+
+    #[test] fn sampled_equities_add_up_to_one() {
+        let mut rng: XorShiftRng = SeedableRng::from_seed([1, 2, 3, 4]);
+        let pockets = vec!["AS KS".parse::<Hand>().ok().unwrap(), "QD QC".parse::<Hand>().ok().unwrap(), "7H 6H".parse::<Hand>().ok().unwrap()];
+        let equities = sampled_equities(&mut rng, 200, &pockets, Hand::new(vec![]));
+        assert!((equities.iter().fold(0.0, |total, equity| total + *equity) - 1.0).abs() < 0.0001);
+    }
+
+    #[test] fn whole_deck_has_fifty_two_cards() {
+        assert_eq!(whole_deck().cards().len(), 52);
+    }
 }
@@ -2,9 +2,11 @@ use std::str::FromStr;
 
 mod value;
 mod suit;
+mod notation;
 
 pub use self::value::Value;
 pub use self::suit::Suit;
+pub use self::notation::{Notation, Notated, strip_colors};
 
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Ord, PartialOrd, Clone)]
 pub struct Card {
@@ -15,17 +17,29 @@ pub struct Card {
 impl FromStr for Card {
     type Err = CardParseErr;
     fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
-        let mut iter = s.chars();
-        let value = match iter.next() {
-            Some(char) => Value::from_char(char),
-            _ => None,
-        };
-        let suit = match iter.next() {
-            Some(char) => Suit::from_char(char),
-            _ => None,
+        let s = strip_colors(s);
+        if s.trim() == "Joker" {
+            return Ok(Card::joker());
+        }
+        let words = s.trim().split_str(" of ").collect::<Vec<&str>>();
+        let (value, suit) = match words.len() {
+            2 => (Value::from_name(words[0]), Suit::from_name(words[1])),
+            _ => {
+                let mut iter = s.chars();
+                let value = match iter.next() {
+                    Some(char) => Value::from_char(char),
+                    _ => None,
+                };
+                let suit = match iter.next() {
+                    Some(char) => Suit::from_char(char),
+                    _ => None,
+                };
+                (value, suit)
+            },
         };
         match (value, suit) {
-            (Some(value), Some(suit)) => Ok(Card::new(value, suit)),
+            // A joker has to be a joker through and through.
+            (Some(value), Some(suit)) if (value == Value::Joker) == (suit == Suit::Joker) => Ok(Card::new(value, suit)),
             _ => Err(CardParseErr::Err),
         }
     }
@@ -40,6 +54,14 @@ impl Card {
         Card { value: value, suit: suit }
     }
 
+    pub fn joker() -> Card {
+        Card::new(Value::Joker, Suit::Joker)
+    }
+
+    pub fn is_joker(&self) -> bool {
+        self.value == Value::Joker
+    }
+
     pub fn value(&self) -> &Value {
         &self.value
     }
@@ -57,4 +79,21 @@ mod test {
         assert!(card.value == Value::Three);
         assert!(card.suit == Suit::Heart);
     }
+
+    #[test] fn cards_parse_in_standard_notation() {
+        let card = "Th".parse::<Card>().ok().unwrap();
+        assert!(card == Card::new(Value::Ten, Suit::Heart));
+    }
+
+    #[test] fn cards_parse_in_long_form() {
+        let card = "Queen of Spades".parse::<Card>().ok().unwrap();
+        assert!(card == Card::new(Value::Queen, Suit::Spade));
+    }
+
+    #[test] fn jokers_parse_as_cards() {
+        assert!("XX".parse::<Card>().ok().unwrap().is_joker());
+        assert!("Joker".parse::<Card>().ok().unwrap() == Card::joker());
+        assert!("XS".parse::<Card>().is_err());
+        assert!("AX".parse::<Card>().is_err());
+    }
 }
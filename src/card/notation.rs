@@ -0,0 +1,170 @@
+use std::fmt;
+
+use super::{Card, Value, Suit};
+
+/// Ways of writing cards down. Everything written in any of them parses back.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Notation {
+    /// The crate's own, the way cards are typed in: "0H", with a zero for ten.
+    Native,
+    /// "Th"
+    Standard,
+    /// "T♥"
+    Unicode,
+    /// "Ten of Hearts"
+    Long,
+    /// Standard notation coloured for a terminal, with each suit its own colour as in a four-colour deck.
+    FourColor,
+}
+
+/// A card, hand, value or suit to be shown in a particular notation, from its `notated`.
+pub struct Notated<'a, T: 'a> {
+    pub item: &'a T,
+    pub notation: Notation,
+}
+
+impl Value {
+    pub fn notated(&self, notation: Notation) -> Notated<Value> {
+        Notated { item: self, notation: notation }
+    }
+}
+
+impl Suit {
+    pub fn notated(&self, notation: Notation) -> Notated<Suit> {
+        Notated { item: self, notation: notation }
+    }
+}
+
+impl Card {
+    pub fn notated(&self, notation: Notation) -> Notated<Card> {
+        Notated { item: self, notation: notation }
+    }
+}
+
+static RESET: &'static str = "\x1b[0m";
+
+fn color(suit: &Suit) -> &'static str {
+    match *suit {
+        Suit::Diamond => "\x1b[34m",
+        Suit::Club => "\x1b[32m",
+        Suit::Heart => "\x1b[31m",
+        Suit::Spade | Suit::Joker => "\x1b[39m",
+    }
+}
+
+/// `text` without any of the terminal colours `FourColor` adds.
+pub fn strip_colors(text: &str) -> String {
+    let mut stripped = String::new();
+    let mut in_escape = false;
+    for char in text.chars() {
+        match (in_escape, char) {
+            (false, '\x1b') => in_escape = true,
+            (false, char) => stripped.push(char),
+            (true, 'm') => in_escape = false,
+            (true, _) => (),
+        }
+    }
+    stripped
+}
+
+impl<'a> fmt::Display for Notated<'a, Value> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.notation, *self.item) {
+            (Notation::Native, value) => write!(f, "{}", value.to_char()),
+            (Notation::Long, value) => write!(f, "{}", value.name()),
+            (_, Value::Ten) => write!(f, "T"),
+            (_, value) => write!(f, "{}", value.to_char()),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Notated<'a, Suit> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let suit = self.item;
+        match self.notation {
+            Notation::Native => write!(f, "{}", suit.to_char()),
+            Notation::Standard => write!(f, "{}", suit.to_lower_char()),
+            Notation::Unicode => write!(f, "{}", suit.symbol()),
+            Notation::Long => write!(f, "{}", suit.plural_name()),
+            Notation::FourColor => write!(f, "{}{}{}", color(suit), suit.to_lower_char(), RESET),
+        }
+    }
+}
+
+impl<'a> fmt::Display for Notated<'a, Card> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (value, suit) = (self.item.value(), self.item.suit());
+        match self.notation {
+            Notation::Long if self.item.is_joker() => write!(f, "Joker"),
+            _ if self.item.is_joker() => write!(f, "XX"),
+            Notation::Long => write!(f, "{} of {}", value.notated(Notation::Long), suit.notated(Notation::Long)),
+            Notation::FourColor => write!(f, "{}{}{}{}", color(suit), value.notated(Notation::Standard),
+                                          suit.notated(Notation::Standard), RESET),
+            notation => write!(f, "{}{}", value.notated(notation), suit.notated(notation)),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.notated(Notation::Native))
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.notated(Notation::Native))
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.notated(Notation::Native))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Notation, strip_colors};
+    use card::{Card, Value, Suit};
+    use calculations::whole_deck;
+
+    static NOTATIONS: [Notation; 5] = [Notation::Native, Notation::Standard, Notation::Unicode, Notation::Long, Notation::FourColor];
+
+    #[test] fn cards_are_written_in_each_notation() {
+        let card = Card::new(Value::Ten, Suit::Heart);
+        assert_eq!(card.to_string(), "0H");
+        assert_eq!(card.notated(Notation::Standard).to_string(), "Th");
+        assert_eq!(card.notated(Notation::Unicode).to_string(), "T♥");
+        assert_eq!(card.notated(Notation::Long).to_string(), "Ten of Hearts");
+        assert_eq!(card.notated(Notation::FourColor).to_string(), "\x1b[31mTh\x1b[0m");
+    }
+
+    #[test] fn jokers_are_written_the_same_way_but_in_long_form() {
+        assert_eq!(Card::joker().to_string(), "XX");
+        assert_eq!(Card::joker().notated(Notation::Unicode).to_string(), "XX");
+        assert_eq!(Card::joker().notated(Notation::Long).to_string(), "Joker");
+    }
+
+    #[test] fn values_and_suits_are_written_on_their_own() {
+        assert_eq!(Value::Queen.to_string(), "Q");
+        assert_eq!(Value::Ten.notated(Notation::Standard).to_string(), "T");
+        assert_eq!(Value::Two.notated(Notation::Long).to_string(), "Two");
+        assert_eq!(Suit::Spade.to_string(), "S");
+        assert_eq!(Suit::Club.notated(Notation::Unicode).to_string(), "♣");
+        assert_eq!(Suit::Diamond.notated(Notation::Long).to_string(), "Diamonds");
+    }
+
+    #[test] fn every_card_parses_back_from_every_notation() {
+        for card in whole_deck().cards().into_iter().chain(Some(Card::joker()).into_iter()) {
+            for &notation in NOTATIONS.iter() {
+                let written = card.notated(notation).to_string();
+                assert_eq!(written.parse::<Card>().ok(), Some(card));
+            }
+        }
+    }
+
+    #[test] fn colors_come_off() {
+        assert_eq!(strip_colors("\x1b[32mAc\x1b[0m \x1b[34m2d\x1b[0m"), "Ac 2d");
+    }
+}
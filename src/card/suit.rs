@@ -4,18 +4,72 @@ pub enum Suit {
     Club,
     Heart,
     Spade,
+    /// A joker's, which has no real suit.
+    Joker,
 }
 
 impl Suit {
     pub fn from_char(char: char) -> Option<Suit> {
         match char {
-            'D' | 'd' => Some(Suit::Diamond),
-            'C' | 'c' => Some(Suit::Club),
-            'H' | 'h' => Some(Suit::Heart),
-            'S' | 's' => Some(Suit::Spade),
+            'D' | 'd' | '♦' | '♢' => Some(Suit::Diamond),
+            'C' | 'c' | '♣' | '♧' => Some(Suit::Club),
+            'H' | 'h' | '♥' | '♡' => Some(Suit::Heart),
+            'S' | 's' | '♠' | '♤' => Some(Suit::Spade),
+            'X' | 'x' => Some(Suit::Joker),
             _ => None,
         }
     }
+
+    pub fn to_char(&self) -> char {
+        match *self {
+            Suit::Diamond => 'D',
+            Suit::Club => 'C',
+            Suit::Heart => 'H',
+            Suit::Spade => 'S',
+            Suit::Joker => 'X',
+        }
+    }
+
+    pub fn to_lower_char(&self) -> char {
+        match *self {
+            Suit::Diamond => 'd',
+            Suit::Club => 'c',
+            Suit::Heart => 'h',
+            Suit::Spade => 's',
+            Suit::Joker => 'x',
+        }
+    }
+
+    pub fn symbol(&self) -> char {
+        match *self {
+            Suit::Diamond => '♦',
+            Suit::Club => '♣',
+            Suit::Heart => '♥',
+            Suit::Spade => '♠',
+            Suit::Joker => 'X',
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Suit> {
+        match name {
+            "Diamonds" | "diamonds" => Some(Suit::Diamond),
+            "Clubs" | "clubs" => Some(Suit::Club),
+            "Hearts" | "hearts" => Some(Suit::Heart),
+            "Spades" | "spades" => Some(Suit::Spade),
+            "Jokers" | "jokers" => Some(Suit::Joker),
+            _ => None,
+        }
+    }
+
+    pub fn plural_name(&self) -> &'static str {
+        match *self {
+            Suit::Diamond => "Diamonds",
+            Suit::Club => "Clubs",
+            Suit::Heart => "Hearts",
+            Suit::Spade => "Spades",
+            Suit::Joker => "Jokers",
+        }
+    }
 }
 
 #[cfg(test)]
@@ -46,6 +100,25 @@ mod tests {
         assert!(Spade != Heart);
     }
 
+    #[test] fn suits_convert_back_to_chars() {
+        for suit in vec![Diamond, Club, Heart, Spade].into_iter() {
+            assert_eq!(super::Suit::from_char(suit.to_char()), Some(suit));
+        }
+    }
+
+    #[test] fn suits_read_their_symbols_and_names() {
+        for suit in vec![Diamond, Club, Heart, Spade].into_iter() {
+            assert_eq!(super::Suit::from_char(suit.symbol()), Some(suit));
+            assert_eq!(super::Suit::from_char(suit.to_lower_char()), Some(suit));
+            assert_eq!(super::Suit::from_name(suit.plural_name()), Some(suit));
+        }
+    }
+
+    #[test] fn jokers_have_a_suit_of_their_own() {
+        assert_eq!(super::Suit::from_char('X'), Some(Joker));
+        assert_eq!(super::Suit::from_char(Joker.to_char()), Some(Joker));
+    }
+
     #[test] fn suits_are_ordered() {
         assert!(Diamond < Club);
         assert!(Diamond < Heart);
@@ -13,6 +13,8 @@ pub enum Value {
     Queen,
     King,
     Ace,
+    /// Not a value of its own: a joker's, which plays as whatever the rules let it.
+    Joker,
 }
 
 impl Value {
@@ -27,13 +29,91 @@ impl Value {
             '7' => Some(Value::Seven),
             '8' => Some(Value::Eight),
             '9' => Some(Value::Nine),
-            '0' => Some(Value::Ten),
+            '0' | 'T' | 't' => Some(Value::Ten),
             'J' | 'j' => Some(Value::Jack),
             'Q' | 'q' => Some(Value::Queen),
             'K' | 'k' => Some(Value::King),
+            'X' | 'x' => Some(Value::Joker),
             _ => None
         }
     }
+
+    pub fn to_char(&self) -> char {
+        match *self {
+            Value::Two => '2',
+            Value::Three => '3',
+            Value::Four => '4',
+            Value::Five => '5',
+            Value::Six => '6',
+            Value::Seven => '7',
+            Value::Eight => '8',
+            Value::Nine => '9',
+            Value::Ten => '0',
+            Value::Jack => 'J',
+            Value::Queen => 'Q',
+            Value::King => 'K',
+            Value::Ace => 'A',
+            Value::Joker => 'X',
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Value::Two => "Two",
+            Value::Three => "Three",
+            Value::Four => "Four",
+            Value::Five => "Five",
+            Value::Six => "Six",
+            Value::Seven => "Seven",
+            Value::Eight => "Eight",
+            Value::Nine => "Nine",
+            Value::Ten => "Ten",
+            Value::Jack => "Jack",
+            Value::Queen => "Queen",
+            Value::King => "King",
+            Value::Ace => "Ace",
+            Value::Joker => "Joker",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Value> {
+        match name {
+            "Two" | "two" => Some(Value::Two),
+            "Three" | "three" => Some(Value::Three),
+            "Four" | "four" => Some(Value::Four),
+            "Five" | "five" => Some(Value::Five),
+            "Six" | "six" => Some(Value::Six),
+            "Seven" | "seven" => Some(Value::Seven),
+            "Eight" | "eight" => Some(Value::Eight),
+            "Nine" | "nine" => Some(Value::Nine),
+            "Ten" | "ten" => Some(Value::Ten),
+            "Jack" | "jack" => Some(Value::Jack),
+            "Queen" | "queen" => Some(Value::Queen),
+            "King" | "king" => Some(Value::King),
+            "Ace" | "ace" => Some(Value::Ace),
+            "Joker" | "joker" => Some(Value::Joker),
+            _ => None,
+        }
+    }
+
+    pub fn plural_name(&self) -> &'static str {
+        match *self {
+            Value::Two => "Twos",
+            Value::Three => "Threes",
+            Value::Four => "Fours",
+            Value::Five => "Fives",
+            Value::Six => "Sixes",
+            Value::Seven => "Sevens",
+            Value::Eight => "Eights",
+            Value::Nine => "Nines",
+            Value::Ten => "Tens",
+            Value::Jack => "Jacks",
+            Value::Queen => "Queens",
+            Value::King => "Kings",
+            Value::Ace => "Aces",
+            Value::Joker => "Jokers",
+        }
+    }
 }
 
 
@@ -72,6 +152,29 @@ mod tests {
         assert!(King != Ace);
     }
 
+    #[test] fn values_convert_back_to_chars() {
+        for value in vec![Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten, Jack, Queen, King, Ace].into_iter() {
+            assert_eq!(super::Value::from_char(value.to_char()), Some(value));
+        }
+    }
+
+    #[test] fn values_read_their_names() {
+        for value in vec![Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten, Jack, Queen, King, Ace].into_iter() {
+            assert_eq!(super::Value::from_name(value.name()), Some(value));
+        }
+    }
+
+    #[test] fn jokers_are_written_as_x() {
+        assert_eq!(super::Value::from_char('X'), Some(Joker));
+        assert_eq!(super::Value::from_char('x'), Some(Joker));
+        assert_eq!(Joker.to_char(), 'X');
+    }
+
+    #[test] fn ten_can_be_written_as_t() {
+        assert_eq!(super::Value::from_char('T'), Some(Ten));
+        assert_eq!(super::Value::from_char('t'), Some(Ten));
+    }
+
     #[test] fn values_are_ordered() {
         assert!(Two < Three);
         assert!(Three < Four);
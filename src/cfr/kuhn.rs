@@ -0,0 +1,86 @@
+use card::{Card, Value, Suit};
+use super::{Game, Node};
+use super::limit;
+
+/// Kuhn poker: a jack, a queen and a king, one card each, an ante of one and a single bet of one.
+#[derive(Debug, Copy, Clone)]
+pub struct Kuhn;
+
+#[derive(Debug, Clone)]
+pub struct KuhnState {
+    cards: Vec<Card>,
+    history: String,
+}
+
+static CARDS: [Value; 3] = [Value::Jack, Value::Queen, Value::King];
+
+impl Game for Kuhn {
+    type State = KuhnState;
+    type Action = char;
+
+    fn root(&self) -> KuhnState {
+        KuhnState { cards: vec![], history: String::new() }
+    }
+
+    fn node(&self, state: &KuhnState) -> Node {
+        match (state.cards.is_empty(), limit::is_over(state.history.as_slice())) {
+            (true, _) => Node::Chance,
+            (false, true) => Node::Terminal,
+            (false, false) => Node::Player(limit::to_act(state.history.as_slice())),
+        }
+    }
+
+    fn payoff(&self, state: &KuhnState) -> f64 {
+        let put_in = limit::contributions(state.history.as_slice(), 1);
+        let winner = match limit::folded(state.history.as_slice()) {
+            Some(folder) => 1 - folder,
+            None if state.cards[0] > state.cards[1] => 0,
+            None => 1,
+        };
+        match winner {
+            0 => 1.0 + put_in[1] as f64,
+            _ => -1.0 - put_in[0] as f64,
+        }
+    }
+
+    fn chance_outcomes(&self, _: &KuhnState) -> Vec<(f64, KuhnState)> {
+        let mut outcomes = vec![];
+        for &first in CARDS.iter() {
+            for &second in CARDS.iter().filter(|&&second| second != first) {
+                let cards = vec![Card::new(first, Suit::Spade), Card::new(second, Suit::Spade)];
+                outcomes.push((1.0 / 6.0, KuhnState { cards: cards, history: String::new() }));
+            }
+        }
+        outcomes
+    }
+
+    fn actions(&self, state: &KuhnState) -> Vec<char> {
+        limit::actions(state.history.as_slice(), 1)
+    }
+
+    fn play(&self, state: &KuhnState, action: char) -> KuhnState {
+        let mut history = state.history.clone();
+        history.push(action);
+        KuhnState { cards: state.cards.clone(), history: history }
+    }
+
+    /// The player's card and the betting, like "Kkb".
+    fn information_set(&self, state: &KuhnState) -> String {
+        let player = limit::to_act(state.history.as_slice());
+        format!("{}{}", state.cards[player].value().to_char(), state.history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Kuhn;
+    use cfr::{Solver, Variant};
+
+    #[test] fn kings_always_call_a_bet() {
+        let mut solver = Solver::new(Kuhn, Variant::Plus);
+        solver.iterate(300);
+        let strategy = solver.average_strategy();
+        assert!(strategy.get("Kb").unwrap()[1] > 0.99);
+        assert!(strategy.get("Jb").unwrap()[0] > 0.99);
+    }
+}
@@ -0,0 +1,155 @@
+use card::{Card, Value, Suit};
+use hand::Hand;
+use super::{Game, Node};
+use super::limit;
+
+use std::cmp::Ordering;
+
+/// Leduc Hold'em: two jacks, two queens and two kings. Each player antes one and gets one card, there's
+/// a round of betting in twos, one card on the board, and a round of betting in fours. Each round has
+/// a bet and at most one raise. Pairing the board wins, and the higher card otherwise.
+#[derive(Debug, Copy, Clone)]
+pub struct Leduc;
+
+#[derive(Debug, Clone)]
+pub struct LeducState {
+    cards: Vec<Card>,
+    rounds: Vec<String>,
+}
+
+static VALUES: [Value; 3] = [Value::Jack, Value::Queen, Value::King];
+static SUITS: [Suit; 2] = [Suit::Heart, Suit::Spade];
+static BETS: [u32; 2] = [2, 4];
+
+impl LeducState {
+    fn round(&self) -> &str {
+        self.rounds.last().unwrap().as_slice()
+    }
+
+    /// What each player has put in, antes included.
+    fn put_in(&self) -> [u32; 2] {
+        self.rounds.iter().zip(BETS.iter()).fold([1, 1], |put_in, (round, &bet)| {
+            let this_round = limit::contributions(round.as_slice(), bet);
+            [put_in[0] + this_round[0], put_in[1] + this_round[1]]
+        })
+    }
+}
+
+fn deck() -> Vec<Card> {
+    VALUES.iter().flat_map(|&value| SUITS.iter().map(move |&suit| Card::new(value, suit))).collect()
+}
+
+impl Game for Leduc {
+    type State = LeducState;
+    type Action = char;
+
+    fn root(&self) -> LeducState {
+        LeducState { cards: vec![], rounds: vec![String::new()] }
+    }
+
+    fn node(&self, state: &LeducState) -> Node {
+        let round = state.round();
+        if state.cards.is_empty() {
+            Node::Chance
+        } else if limit::folded(round).is_some() {
+            Node::Terminal
+        } else if limit::is_over(round) {
+            match state.rounds.len() {
+                1 => Node::Chance,
+                _ => Node::Terminal,
+            }
+        } else {
+            Node::Player(limit::to_act(round))
+        }
+    }
+
+    fn payoff(&self, state: &LeducState) -> f64 {
+        let put_in = state.put_in();
+        let winner = match limit::folded(state.round()) {
+            Some(folder) => Some(1 - folder),
+            None => {
+                let first = Hand::new(vec![state.cards[0], state.cards[2]]);
+                let second = Hand::new(vec![state.cards[1], state.cards[2]]);
+                match first.cmp(&second) {
+                    Ordering::Greater => Some(0),
+                    Ordering::Less => Some(1),
+                    Ordering::Equal => None,
+                }
+            },
+        };
+        match winner {
+            Some(0) => put_in[1] as f64,
+            Some(_) => -(put_in[0] as f64),
+            None => 0.0,
+        }
+    }
+
+    fn chance_outcomes(&self, state: &LeducState) -> Vec<(f64, LeducState)> {
+        let deck = deck();
+        match state.cards.is_empty() {
+            true => {
+                let mut outcomes = vec![];
+                for &first in deck.iter() {
+                    for &second in deck.iter().filter(|&&second| second != first) {
+                        outcomes.push((1.0 / 30.0, LeducState { cards: vec![first, second], rounds: state.rounds.clone() }));
+                    }
+                }
+                outcomes
+            },
+            false => deck.iter()
+                     .filter(|&card| !state.cards.contains(card))
+                     .map(|&board| {
+                         let mut cards = state.cards.clone();
+                         cards.push(board);
+                         let mut rounds = state.rounds.clone();
+                         rounds.push(String::new());
+                         (1.0 / 4.0, LeducState { cards: cards, rounds: rounds })
+                     })
+                     .collect(),
+        }
+    }
+
+    fn actions(&self, state: &LeducState) -> Vec<char> {
+        limit::actions(state.round(), 2)
+    }
+
+    fn play(&self, state: &LeducState, action: char) -> LeducState {
+        let mut rounds = state.rounds.clone();
+        rounds.last_mut().unwrap().push(action);
+        LeducState { cards: state.cards.clone(), rounds: rounds }
+    }
+
+    /// The player's card, the board card if it's out, and the betting by round, like "QK:kbc/b".
+    /// Suits never matter, so they're left out.
+    fn information_set(&self, state: &LeducState) -> String {
+        let player = limit::to_act(state.round());
+        let board = match state.cards.get(2) {
+            Some(card) => card.value().to_char().to_string(),
+            None => String::new(),
+        };
+        format!("{}{}:{}", state.cards[player].value().to_char(), board, state.rounds.connect("/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Leduc;
+    use cfr::{Solver, Strategy, Variant, exploitability};
+
+    #[test] fn cfr_plus_makes_leduc_much_harder_to_exploit() {
+        let mut solver = Solver::new(Leduc, Variant::Plus);
+        solver.iterate(100);
+        let solved = exploitability(&Leduc, &solver.average_strategy());
+        assert!(solved < 0.1);
+        assert!(solved < exploitability(&Leduc, &Strategy::new()) / 10.0);
+    }
+
+    #[test] fn the_betting_is_in_the_information_set() {
+        let mut solver = Solver::new(Leduc, Variant::Vanilla);
+        solver.iterate(1);
+        let strategy = solver.average_strategy();
+        assert!(strategy.get("K:").is_some());
+        assert!(strategy.get("KK:kbc/").is_some());
+        assert_eq!(strategy.get("K:kbb").unwrap().len(), 2);
+    }
+}
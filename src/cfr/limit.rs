@@ -0,0 +1,65 @@
+// Betting rounds in fixed-limit games, written as strings of actions: `k` checks, `b` bets or raises,
+// `c` calls and `f` folds. Player 0 acts first in every round.
+
+/// What the player to act can do, with at most `max_bets` bets and raises in the round.
+pub fn actions(round: &str, max_bets: usize) -> Vec<char> {
+    let bets = round.chars().filter(|&action| action == 'b').count();
+    match round.ends_with("b") {
+        true if bets < max_bets => vec!['f', 'c', 'b'],
+        true => vec!['f', 'c'],
+        false => vec!['k', 'b'],
+    }
+}
+
+pub fn to_act(round: &str) -> usize {
+    round.len() % 2
+}
+
+pub fn is_over(round: &str) -> bool {
+    round.ends_with("f") || round.ends_with("c") || round == "kk"
+}
+
+/// The player who folded, if the round ended that way.
+pub fn folded(round: &str) -> Option<usize> {
+    match round.ends_with("f") {
+        true => Some((round.len() - 1) % 2),
+        false => None,
+    }
+}
+
+/// What each player put in during the round, with bets and raises of `bet`.
+pub fn contributions(round: &str, bet: u32) -> [u32; 2] {
+    let mut put_in = [0, 0];
+    for (turn, action) in round.chars().enumerate() {
+        let highest = if put_in[0] > put_in[1] { put_in[0] } else { put_in[1] };
+        match action {
+            'b' => put_in[turn % 2] = highest + bet,
+            'c' => put_in[turn % 2] = highest,
+            _ => (),
+        }
+    }
+    put_in
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{actions, is_over, folded, contributions};
+
+    #[test] fn raises_stop_at_the_cap() {
+        assert_eq!(actions("", 2), vec!['k', 'b']);
+        assert_eq!(actions("kb", 2), vec!['f', 'c', 'b']);
+        assert_eq!(actions("kbb", 2), vec!['f', 'c']);
+    }
+
+    #[test] fn rounds_end_on_a_call_a_fold_or_two_checks() {
+        assert!(is_over("kk") && is_over("bc") && is_over("kbf"));
+        assert!(!is_over("k") && !is_over("kb"));
+        assert_eq!(folded("kbf"), Some(0));
+        assert_eq!(folded("bc"), None);
+    }
+
+    #[test] fn raises_match_then_add_a_bet() {
+        assert_eq!(contributions("kbbc", 2), [4, 4]);
+        assert_eq!(contributions("bf", 4), [4, 0]);
+    }
+}
@@ -0,0 +1,475 @@
+use std::collections::HashMap;
+use std::old_io::IoResult;
+use std::rand::{thread_rng, Rng};
+
+mod limit;
+pub mod kuhn;
+pub mod leduc;
+pub mod river;
+
+/// What happens at a point in a game: it's over, cards are dealt, or a player decides.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Node {
+    Terminal,
+    Chance,
+    Player(usize),
+}
+
+/// A two-player zero-sum game with hidden information, as a tree of states.
+pub trait Game {
+    type State: Clone;
+    type Action: Copy;
+
+    fn root(&self) -> Self::State;
+    fn node(&self, state: &Self::State) -> Node;
+    /// What player 0 wins at a terminal state; player 1 wins the negative of it.
+    fn payoff(&self, state: &Self::State) -> f64;
+    /// The states a chance node can lead to, with how likely each one is.
+    fn chance_outcomes(&self, state: &Self::State) -> Vec<(f64, Self::State)>;
+    fn actions(&self, state: &Self::State) -> Vec<Self::Action>;
+    fn play(&self, state: &Self::State, action: Self::Action) -> Self::State;
+    /// Names what the player to act knows, so states they can't tell apart share a name.
+    /// Names can't contain tabs or line breaks.
+    fn information_set(&self, state: &Self::State) -> String;
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Variant {
+    /// Counterfactual regret minimization, visiting the whole tree every iteration.
+    Vanilla,
+    /// CFR+: regrets never go below zero and later iterations count for more in the average.
+    Plus,
+    /// Monte Carlo CFR with external sampling: chance and the opponent are sampled, one path each.
+    ExternalSampling,
+}
+
+/// Learns a strategy for a game by playing it against itself, one player's regrets at a time.
+pub struct Solver<G: Game> {
+    game: G,
+    variant: Variant,
+    regrets: HashMap<String, Vec<f64>>,
+    strategy_sums: HashMap<String, Vec<f64>>,
+    iterations: usize,
+}
+
+impl<G: Game> Solver<G> {
+    pub fn new(game: G, variant: Variant) -> Solver<G> {
+        Solver { game: game, variant: variant, regrets: HashMap::new(), strategy_sums: HashMap::new(), iterations: 0 }
+    }
+
+    pub fn game(&self) -> &G {
+        &self.game
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    pub fn iterate(&mut self, iterations: usize) {
+        let mut rng = thread_rng();
+        let root = self.game.root();
+        for _ in range(0, iterations) {
+            self.iterations += 1;
+            for player in range(0, 2) {
+                match self.variant {
+                    Variant::ExternalSampling => { self.sample(&root, player, &mut rng); },
+                    _ => { self.traverse(&root, player, 1.0, 1.0); },
+                }
+            }
+        }
+    }
+
+    /// The average of every strategy played so far, which is what converges to an equilibrium.
+    pub fn average_strategy(&self) -> Strategy {
+        let probabilities = self.strategy_sums.iter()
+                            .map(|(information_set, sums)| (information_set.clone(), normalized(sums)))
+                            .collect();
+        Strategy { probabilities: probabilities }
+    }
+
+    /// The strategy regret matching would play next at `information_set`.
+    fn current_strategy(&self, information_set: &String, actions: usize) -> Vec<f64> {
+        match self.regrets.get(information_set) {
+            Some(regrets) => normalized(&regrets.iter().map(|&regret| if regret > 0.0 { regret } else { 0.0 }).collect()),
+            None => uniform(actions),
+        }
+    }
+
+    /// `updating`'s value at `state`, updating their regrets and strategy sums on the way.
+    /// `own_reach` is how likely their own play is to get here, `others_reach` chance's and the opponent's.
+    fn traverse(&mut self, state: &G::State, updating: usize, own_reach: f64, others_reach: f64) -> f64 {
+        match self.game.node(state) {
+            Node::Terminal => signed(self.game.payoff(state), updating),
+            Node::Chance => {
+                let outcomes = self.game.chance_outcomes(state);
+                outcomes.iter().fold(0.0, |value, &(chance, ref next)| {
+                    value + chance * self.traverse(next, updating, own_reach, others_reach * chance)
+                })
+            },
+            Node::Player(player) => {
+                let information_set = self.game.information_set(state);
+                let children = self.game.actions(state).iter().map(|&action| self.game.play(state, action)).collect::<Vec<_>>();
+                let strategy = self.current_strategy(&information_set, children.len());
+                if player != updating {
+                    return children.iter().zip(strategy.iter()).fold(0.0, |value, (next, &probability)| {
+                        value + probability * self.traverse(next, updating, own_reach, others_reach * probability)
+                    });
+                }
+                let values = children.iter().zip(strategy.iter())
+                             .map(|(next, &probability)| self.traverse(next, updating, own_reach * probability, others_reach))
+                             .collect::<Vec<f64>>();
+                let value = values.iter().zip(strategy.iter()).fold(0.0, |value, (action_value, probability)| value + action_value * probability);
+                let plus = self.variant == Variant::Plus;
+                let weight = match plus {
+                    true => self.iterations as f64,
+                    false => 1.0,
+                };
+                for (regret, action_value) in entry(&mut self.regrets, &information_set, children.len()).iter_mut().zip(values.iter()) {
+                    *regret += others_reach * (*action_value - value);
+                    if plus && *regret < 0.0 {
+                        *regret = 0.0;
+                    }
+                }
+                for (sum, probability) in entry(&mut self.strategy_sums, &information_set, children.len()).iter_mut().zip(strategy.iter()) {
+                    *sum += weight * own_reach * *probability;
+                }
+                value
+            },
+        }
+    }
+
+    /// Like `traverse`, following one sampled deal and one sampled action of the opponent's.
+    fn sample<R: Rng>(&mut self, state: &G::State, updating: usize, rng: &mut R) -> f64 {
+        match self.game.node(state) {
+            Node::Terminal => signed(self.game.payoff(state), updating),
+            Node::Chance => {
+                let outcomes = self.game.chance_outcomes(state);
+                let chances = outcomes.iter().map(|&(chance, _)| chance).collect::<Vec<f64>>();
+                let picked = pick(&chances, rng);
+                self.sample(&outcomes[picked].1, updating, rng)
+            },
+            Node::Player(player) => {
+                let information_set = self.game.information_set(state);
+                let actions = self.game.actions(state);
+                let strategy = self.current_strategy(&information_set, actions.len());
+                if player != updating {
+                    for (sum, probability) in entry(&mut self.strategy_sums, &information_set, actions.len()).iter_mut().zip(strategy.iter()) {
+                        *sum += *probability;
+                    }
+                    let next = self.game.play(state, actions[pick(&strategy, rng)]);
+                    return self.sample(&next, updating, rng);
+                }
+                let values = actions.iter()
+                             .map(|&action| {
+                                 let next = self.game.play(state, action);
+                                 self.sample(&next, updating, rng)
+                             })
+                             .collect::<Vec<f64>>();
+                let value = values.iter().zip(strategy.iter()).fold(0.0, |value, (action_value, probability)| value + action_value * probability);
+                for (regret, action_value) in entry(&mut self.regrets, &information_set, actions.len()).iter_mut().zip(values.iter()) {
+                    *regret += *action_value - value;
+                }
+                value
+            },
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum StrategyParseErr {
+    Unreadable,
+    BadHeader,
+    BadLine(String),
+}
+
+static STRATEGY_HEADER: &'static str = "cfr strategy 1";
+
+/// How likely each action is at every information set, in the order the game lists the actions.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Strategy {
+    probabilities: HashMap<String, Vec<f64>>,
+}
+
+impl Strategy {
+    pub fn new() -> Strategy {
+        Strategy { probabilities: HashMap::new() }
+    }
+
+    pub fn get(&self, information_set: &str) -> Option<&Vec<f64>> {
+        self.probabilities.get(&information_set.to_string())
+    }
+
+    pub fn set(&mut self, information_set: &str, probabilities: Vec<f64>) {
+        self.probabilities.insert(information_set.to_string(), probabilities);
+    }
+
+    /// The probabilities at `information_set`, or an even split between the actions if it's never been reached.
+    pub fn probabilities(&self, information_set: &String, actions: usize) -> Vec<f64> {
+        match self.probabilities.get(information_set) {
+            Some(probabilities) if probabilities.len() == actions => probabilities.clone(),
+            _ => uniform(actions),
+        }
+    }
+
+    pub fn information_sets(&self) -> usize {
+        self.probabilities.len()
+    }
+
+    /// A header line, then one line per information set: its name, a tab, and the probabilities.
+    pub fn write<W: Writer>(&self, writer: &mut W) -> IoResult<()> {
+        try!(writer.write_line(STRATEGY_HEADER));
+        let mut information_sets = self.probabilities.keys().collect::<Vec<&String>>();
+        information_sets.sort();
+        for information_set in information_sets.into_iter() {
+            let probabilities = self.probabilities.get(*information_set).unwrap().iter().map(|probability| probability.to_string()).collect::<Vec<String>>();
+            try!(writer.write_line(format!("{}\t{}", information_set, probabilities.connect(" ")).as_slice()));
+        }
+        Ok(())
+    }
+
+    pub fn read<B: Buffer>(reader: &mut B) -> Result<Strategy, StrategyParseErr> {
+        let mut lines = reader.lines();
+        match lines.next() {
+            Some(Ok(ref header)) if header.trim() == STRATEGY_HEADER => (),
+            Some(Ok(_)) | None => return Err(StrategyParseErr::BadHeader),
+            Some(Err(_)) => return Err(StrategyParseErr::Unreadable),
+        }
+        let mut strategy = Strategy::new();
+        for line in lines {
+            let line = match line {
+                Ok(line) => line.trim_right_matches('\n').to_string(),
+                Err(_) => return Err(StrategyParseErr::Unreadable),
+            };
+            if line.is_empty() {
+                continue;
+            }
+            let parts = line.split('\t').collect::<Vec<&str>>();
+            if parts.len() != 2 {
+                return Err(StrategyParseErr::BadLine(line.clone()));
+            }
+            let probabilities = parts[1].split(' ').map(|probability| probability.parse::<f64>().ok()).collect::<Vec<Option<f64>>>();
+            if probabilities.iter().any(|probability| probability.is_none()) {
+                return Err(StrategyParseErr::BadLine(line.clone()));
+            }
+            strategy.set(parts[0], probabilities.into_iter().map(|probability| probability.unwrap()).collect());
+        }
+        Ok(strategy)
+    }
+}
+
+/// What player 0 expects to win with both players following `strategy`.
+pub fn expected_payoff<G: Game>(game: &G, strategy: &Strategy) -> f64 {
+    expected_payoff_from(game, strategy, &game.root())
+}
+
+/// Like `expected_payoff`, from `state` on.
+pub fn expected_payoff_from<G: Game>(game: &G, strategy: &Strategy, state: &G::State) -> f64 {
+    match game.node(state) {
+        Node::Terminal => game.payoff(state),
+        Node::Chance => game.chance_outcomes(state).iter().fold(0.0, |value, &(chance, ref next)| {
+            value + chance * expected_payoff_from(game, strategy, next)
+        }),
+        Node::Player(_) => {
+            let actions = game.actions(state);
+            let probabilities = strategy.probabilities(&game.information_set(state), actions.len());
+            actions.iter().zip(probabilities.iter()).fold(0.0, |value, (&action, &probability)| {
+                value + probability * expected_payoff_from(game, strategy, &game.play(state, action))
+            })
+        },
+    }
+}
+
+/// What `player` wins by playing the best response to the other player's half of `strategy`.
+pub fn best_response_value<G: Game>(game: &G, strategy: &Strategy, player: usize) -> f64 {
+    let mut best_response = BestResponse {
+        game: game,
+        strategy: strategy,
+        player: player,
+        reached: HashMap::new(),
+        best: HashMap::new(),
+    };
+    let root = game.root();
+    best_response.reach(&root, 1.0);
+    best_response.value(&root)
+}
+
+/// How much a best response wins against `strategy`, averaged over the two seats. Zero at an equilibrium.
+pub fn exploitability<G: Game>(game: &G, strategy: &Strategy) -> f64 {
+    (best_response_value(game, strategy, 0) + best_response_value(game, strategy, 1)) / 2.0
+}
+
+/// Works out a best response an information set at a time: the action taken at one is whichever does
+/// best summed over every state in it, weighted by how likely chance and the opponent are to get there.
+struct BestResponse<'a, G: Game + 'a> {
+    game: &'a G,
+    strategy: &'a Strategy,
+    player: usize,
+    reached: HashMap<String, Vec<(G::State, f64)>>,
+    best: HashMap<String, usize>,
+}
+
+impl<'a, G: Game> BestResponse<'a, G> {
+    fn reach(&mut self, state: &G::State, reach: f64) {
+        match self.game.node(state) {
+            Node::Terminal => (),
+            Node::Chance => {
+                for &(chance, ref next) in self.game.chance_outcomes(state).iter() {
+                    self.reach(next, reach * chance);
+                }
+            },
+            Node::Player(player) => {
+                let information_set = self.game.information_set(state);
+                let actions = self.game.actions(state);
+                let probabilities = match player == self.player {
+                    true => {
+                        if !self.reached.contains_key(&information_set) {
+                            self.reached.insert(information_set.clone(), vec![]);
+                        }
+                        self.reached.get_mut(&information_set).unwrap().push((state.clone(), reach));
+                        actions.iter().map(|_| 1.0).collect()
+                    },
+                    false => self.strategy.probabilities(&information_set, actions.len()),
+                };
+                for (&action, probability) in actions.iter().zip(probabilities.iter()) {
+                    let next = self.game.play(state, action);
+                    self.reach(&next, reach * *probability);
+                }
+            },
+        }
+    }
+
+    fn value(&mut self, state: &G::State) -> f64 {
+        match self.game.node(state) {
+            Node::Terminal => signed(self.game.payoff(state), self.player),
+            Node::Chance => self.game.chance_outcomes(state).iter().fold(0.0, |value, &(chance, ref next)| {
+                value + chance * self.value(next)
+            }),
+            Node::Player(player) => {
+                let information_set = self.game.information_set(state);
+                let actions = self.game.actions(state);
+                if player == self.player {
+                    let best = self.best_action(information_set);
+                    let next = self.game.play(state, actions[best]);
+                    return self.value(&next);
+                }
+                let probabilities = self.strategy.probabilities(&information_set, actions.len());
+                actions.iter().zip(probabilities.iter()).fold(0.0, |value, (&action, &probability)| {
+                    let next = self.game.play(state, action);
+                    value + probability * self.value(&next)
+                })
+            },
+        }
+    }
+
+    fn best_action(&mut self, information_set: String) -> usize {
+        match self.best.get(&information_set) {
+            Some(&best) => return best,
+            None => (),
+        }
+        let reached = self.reached.get(&information_set).unwrap().clone();
+        let actions = self.game.actions(&reached[0].0);
+        let mut totals = actions.iter().map(|_| 0.0).collect::<Vec<f64>>();
+        for &(ref state, reach) in reached.iter() {
+            for (total, &action) in totals.iter_mut().zip(actions.iter()) {
+                let next = self.game.play(state, action);
+                *total += reach * self.value(&next);
+            }
+        }
+        let best = range(0, totals.len()).fold(0, |best, action| if totals[action] > totals[best] { action } else { best });
+        self.best.insert(information_set, best);
+        best
+    }
+}
+
+fn signed(payoff: f64, player: usize) -> f64 {
+    match player {
+        0 => payoff,
+        _ => -payoff,
+    }
+}
+
+fn uniform(actions: usize) -> Vec<f64> {
+    range(0, actions).map(|_| 1.0 / actions as f64).collect()
+}
+
+/// `weights` scaled to add up to one, or an even split if they're all zero.
+fn normalized(weights: &Vec<f64>) -> Vec<f64> {
+    let total = weights.iter().fold(0.0, |total, weight| total + *weight);
+    match total > 0.0 {
+        true => weights.iter().map(|weight| weight / total).collect(),
+        false => uniform(weights.len()),
+    }
+}
+
+fn entry<'a>(table: &'a mut HashMap<String, Vec<f64>>, information_set: &String, actions: usize) -> &'a mut Vec<f64> {
+    if !table.contains_key(information_set) {
+        table.insert(information_set.clone(), range(0, actions).map(|_| 0.0).collect());
+    }
+    table.get_mut(information_set).unwrap()
+}
+
+/// An index drawn with the given probabilities.
+fn pick<R: Rng>(probabilities: &Vec<f64>, rng: &mut R) -> usize {
+    let mut left = rng.gen::<f64>();
+    for (index, probability) in probabilities.iter().enumerate() {
+        if left < *probability {
+            return index;
+        }
+        left -= *probability;
+    }
+    probabilities.len() - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Solver, Strategy, Variant, StrategyParseErr, exploitability, expected_payoff, best_response_value};
+    use super::kuhn::Kuhn;
+    use std::num::Float;
+    use std::old_io::BufReader;
+
+    #[test] fn cfr_finds_the_kuhn_equilibrium() {
+        let mut solver = Solver::new(Kuhn, Variant::Vanilla);
+        solver.iterate(1000);
+        let strategy = solver.average_strategy();
+        assert!(exploitability(&Kuhn, &strategy) < 0.01);
+        // The first player loses a 1/18 of a chip a hand at equilibrium.
+        assert!((expected_payoff(&Kuhn, &strategy) + 1.0 / 18.0).abs() < 0.01);
+    }
+
+    #[test] fn cfr_plus_converges_too() {
+        let mut solver = Solver::new(Kuhn, Variant::Plus);
+        solver.iterate(300);
+        assert!(exploitability(&Kuhn, &solver.average_strategy()) < 0.01);
+    }
+
+    #[test] fn sampling_gets_close() {
+        let mut solver = Solver::new(Kuhn, Variant::ExternalSampling);
+        solver.iterate(20000);
+        assert!(exploitability(&Kuhn, &solver.average_strategy()) < 0.05);
+    }
+
+    #[test] fn playing_at_random_can_be_exploited() {
+        let strategy = Strategy::new();
+        assert!(best_response_value(&Kuhn, &strategy, 0) > 0.0);
+        assert!(exploitability(&Kuhn, &strategy) > 0.1);
+    }
+
+    #[test] fn strategies_survive_being_saved_and_loaded() {
+        let mut solver = Solver::new(Kuhn, Variant::Plus);
+        solver.iterate(10);
+        let strategy = solver.average_strategy();
+        let mut bytes = vec![];
+        strategy.write(&mut bytes).ok().unwrap();
+        let loaded = Strategy::read(&mut BufReader::new(bytes.as_slice())).ok().unwrap();
+        assert_eq!(loaded.information_sets(), 12);
+        for information_set in ["K", "Qb", "Jkb"].iter() {
+            let (saved, loaded) = (strategy.get(*information_set).unwrap(), loaded.get(*information_set).unwrap());
+            assert!(saved.iter().zip(loaded.iter()).all(|(saved, loaded)| (*saved - *loaded).abs() < 1e-9));
+        }
+    }
+
+    #[test] fn strategy_files_must_have_the_header() {
+        assert_eq!(Strategy::read(&mut BufReader::new(b"K\t0.5 0.5\n")), Err(StrategyParseErr::BadHeader));
+        assert_eq!(Strategy::read(&mut BufReader::new(b"cfr strategy 1\nK\t0.5 x\n")), Err(StrategyParseErr::BadLine("K\t0.5 x".to_string())));
+    }
+}
@@ -0,0 +1,136 @@
+use hand::Hand;
+use super::{Game, Node};
+use super::limit;
+
+use std::cmp::Ordering;
+
+/// The river of a heads-up limit hold'em hand: the board is out, each player holds one of a list of
+/// hands, and there's a pot to play for with bets and raises of a fixed size.
+/// Player 0 is out of position and acts first. Payoffs count the pot as half each player's already.
+#[derive(Debug, Clone)]
+pub struct RiverGame {
+    hands: Vec<Vec<Hand>>,
+    pot: u32,
+    bet: u32,
+    max_bets: usize,
+    deals: Vec<(usize, usize)>,
+    showdowns: Vec<Ordering>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RiverState {
+    deal: Option<usize>,
+    history: String,
+}
+
+impl RiverGame {
+    /// Each pair of `first` and `second` hands that don't share a card with each other or `board`
+    /// is as likely to be dealt as any other.
+    pub fn new(board: Hand, first: Vec<Hand>, second: Vec<Hand>, pot: u32, bet: u32) -> RiverGame {
+        let mut deals = vec![];
+        let mut showdowns = vec![];
+        for (first_index, first_hand) in first.iter().enumerate() {
+            for (second_index, second_hand) in second.iter().enumerate() {
+                let cards = first_hand.clone() + second_hand.clone() + board.clone();
+                if cards.cards().len() != first_hand.cards().len() + second_hand.cards().len() + board.cards().len() {
+                    continue;
+                }
+                deals.push((first_index, second_index));
+                showdowns.push((first_hand.clone() + board.clone()).cmp(&(second_hand.clone() + board.clone())));
+            }
+        }
+        RiverGame { hands: vec![first, second], pot: pot, bet: bet, max_bets: 4, deals: deals, showdowns: showdowns }
+    }
+
+    /// Caps the bets and raises, four by default.
+    pub fn with_max_bets(mut self, max_bets: usize) -> RiverGame {
+        self.max_bets = max_bets;
+        self
+    }
+}
+
+impl Game for RiverGame {
+    type State = RiverState;
+    type Action = char;
+
+    fn root(&self) -> RiverState {
+        RiverState { deal: None, history: String::new() }
+    }
+
+    fn node(&self, state: &RiverState) -> Node {
+        match (state.deal, limit::is_over(state.history.as_slice())) {
+            (None, _) => Node::Chance,
+            (Some(_), true) => Node::Terminal,
+            (Some(_), false) => Node::Player(limit::to_act(state.history.as_slice())),
+        }
+    }
+
+    fn payoff(&self, state: &RiverState) -> f64 {
+        let put_in = limit::contributions(state.history.as_slice(), self.bet);
+        let half_pot = self.pot as f64 / 2.0;
+        let winner = match limit::folded(state.history.as_slice()) {
+            Some(folder) => Some(1 - folder),
+            None => match self.showdowns[state.deal.unwrap()] {
+                Ordering::Greater => Some(0),
+                Ordering::Less => Some(1),
+                Ordering::Equal => None,
+            },
+        };
+        match winner {
+            Some(0) => half_pot + put_in[1] as f64,
+            Some(_) => -half_pot - put_in[0] as f64,
+            None => 0.0,
+        }
+    }
+
+    fn chance_outcomes(&self, _: &RiverState) -> Vec<(f64, RiverState)> {
+        let chance = 1.0 / self.deals.len() as f64;
+        range(0, self.deals.len()).map(|deal| (chance, RiverState { deal: Some(deal), history: String::new() })).collect()
+    }
+
+    fn actions(&self, state: &RiverState) -> Vec<char> {
+        limit::actions(state.history.as_slice(), self.max_bets)
+    }
+
+    fn play(&self, state: &RiverState, action: char) -> RiverState {
+        let mut history = state.history.clone();
+        history.push(action);
+        RiverState { deal: state.deal, history: history }
+    }
+
+    /// The player's hole cards and the betting, like "ASKD:kb".
+    fn information_set(&self, state: &RiverState) -> String {
+        let player = limit::to_act(state.history.as_slice());
+        let (first, second) = self.deals[state.deal.unwrap()];
+        let hand = &self.hands[player][if player == 0 { first } else { second }];
+        let cards = hand.cards().iter()
+                    .map(|card| format!("{}{}", card.value().to_char(), card.suit().to_char()))
+                    .collect::<Vec<String>>();
+        format!("{}:{}", cards.concat(), state.history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RiverGame;
+    use hand::Hand;
+    use cfr::{Solver, Variant, exploitability};
+
+    fn hand(s: &str) -> Hand {
+        s.parse().ok().unwrap()
+    }
+
+    #[test] fn air_folds_to_the_nuts() {
+        let game = RiverGame::new(hand("AS KS QS 2D 7C"), vec![hand("JS 0S")], vec![hand("3H 4H"), hand("3C 4C")], 10, 2);
+        let mut solver = Solver::new(game.clone(), Variant::Plus);
+        solver.iterate(200);
+        let strategy = solver.average_strategy();
+        assert!(strategy.get("3H4H:b").unwrap()[0] > 0.95);
+        assert!(exploitability(&game, &strategy) < 0.05);
+    }
+
+    #[test] fn hands_that_share_cards_are_never_dealt_together() {
+        let game = RiverGame::new(hand("AS KS QS 2D 7C"), vec![hand("JS 0S"), hand("AH AD")], vec![hand("AH AC")], 10, 2);
+        assert_eq!(game.deals.len(), 1);
+    }
+}
@@ -0,0 +1,148 @@
+use card::Card;
+use hand::{Hand, HandRank};
+use calculations::{whole_deck, confidence_interval, SAMPLES};
+
+use std::collections::HashMap;
+use std::rand::{thread_rng, Rng};
+
+/// One way to play a five-card draw hand: the cards kept and the cards thrown away.
+#[derive(Debug, Clone)]
+pub struct DrawChoice {
+    kept: Hand,
+    discarded: Hand,
+}
+
+impl DrawChoice {
+    pub fn kept(&self) -> &Hand {
+        &self.kept
+    }
+
+    pub fn discarded(&self) -> &Hand {
+        &self.discarded
+    }
+
+    /// The final hand after drawing replacements off the top of a shuffled deck.
+    pub fn draw(&self, deck: &mut Vec<Card>) -> Hand {
+        let needed = self.discarded.cards().len();
+        let drawn = range(0, needed).filter_map(|_| deck.pop()).collect();
+        self.kept.clone() + Hand::new(drawn)
+    }
+}
+
+/// All 32 ways of discarding from a five-card hand, from standing pat to drawing five.
+pub fn discard_choices(hand: &Hand) -> Vec<DrawChoice> {
+    let cards = hand.cards();
+    range(0, 1 << cards.len()).rev()
+        .map(|kept_mask: usize| {
+            let keeps = |keep: bool| {
+                cards.iter()
+                     .enumerate()
+                     .filter(|&(index, _)| (kept_mask & (1 << index) != 0) == keep)
+                     .map(|(_, &card)| card)
+                     .collect::<Vec<Card>>()
+            };
+            DrawChoice { kept: Hand::new(keeps(true)), discarded: Hand::new(keeps(false)) }
+        })
+        .collect()
+}
+
+fn shuffled_deck(hand: &Hand) -> Vec<Card> {
+    let mut deck = (whole_deck() - hand.clone()).cards();
+    thread_rng().shuffle(deck.as_mut_slice());
+    deck
+}
+
+/// How often each `HandRank` is made by drawing to `choice`.
+pub fn rank_distribution(hand: &Hand, choice: &DrawChoice) -> HashMap<HandRank, f32> {
+    let counts = range(0, SAMPLES)
+        .map(|_| choice.draw(&mut shuffled_deck(hand)).rank())
+        .fold(HashMap::new(), |mut map, rank| {
+            let new_count = match map.get(&rank) {
+                Some(count) => count + 1.0,
+                None => 1.0,
+            };
+            map.insert(rank, new_count);
+            map
+        });
+    counts.into_iter().map(|(rank, count)| (rank, count / SAMPLES as f32)).collect()
+}
+
+/// The chance of drawing to `choice` and beating every opponent.
+/// Opponents are dealt five cards from what's left of the deck and stand pat.
+pub fn chance_of_winning(hand: &Hand, choice: &DrawChoice, other_players: usize) -> (f32, f32) {
+    let results = range(0, SAMPLES)
+        .map(|_| {
+            let mut deck = shuffled_deck(hand);
+            let final_hand = choice.draw(&mut deck);
+            range(0, other_players)
+                .map(|_| Hand::new(range(0, 5).filter_map(|_| deck.pop()).collect()))
+                .all(|opponents_hand| final_hand > opponents_hand)
+        }).collect::<Vec<bool>>();
+    let total: f32 = results.iter().fold(0.0, |sum, _| sum + 1.0);
+    let wins: f32 = results.iter().filter(|result| **result).fold(0.0, |sum, _| sum + 1.0);
+    (wins / total, confidence_interval(wins, total))
+}
+
+/// The draw with the best chance of winning against `other_players`, with that chance.
+pub fn best_draw(hand: &Hand, other_players: usize) -> (DrawChoice, f32) {
+    discard_choices(hand).into_iter()
+        .map(|choice| {
+            let (chance, _) = chance_of_winning(hand, &choice, other_players);
+            (choice, chance)
+        })
+        .fold(None, |best: Option<(DrawChoice, f32)>, (choice, chance)| {
+            match best {
+                Some((best_choice, best_chance)) => match chance > best_chance {
+                    true => Some((choice, chance)),
+                    false => Some((best_choice, best_chance)),
+                },
+                None => Some((choice, chance)),
+            }
+        })
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{discard_choices, rank_distribution, best_draw};
+    use card::Value;
+    use hand::{Hand, HandRank};
+
+    fn parse_hand(s: &str) -> Hand {
+        s.parse().ok().unwrap()
+    }
+
+    #[test] fn there_are_thirty_two_ways_to_discard() {
+        assert_eq!(discard_choices(&parse_hand("AS KS QS JS 9D")).len(), 32);
+    }
+
+    #[test] fn first_choice_stands_pat() {
+        let choices = discard_choices(&parse_hand("AS KS QS JS 9D"));
+        assert_eq!(choices[0].kept().cards().len(), 5);
+        assert_eq!(choices[0].discarded().cards().len(), 0);
+    }
+
+    #[test] fn every_choice_keeps_or_discards_each_card() {
+        for choice in discard_choices(&parse_hand("AS KS QS JS 9D")).iter() {
+            assert_eq!(choice.kept().cards().len() + choice.discarded().cards().len(), 5);
+        }
+    }
+
+    #[test] fn standing_pat_keeps_the_same_rank() {
+        let hand = parse_hand("AS AH AD KS KD");
+        let choices = discard_choices(&hand);
+        let distribution = rank_distribution(&hand, &choices[0]);
+        assert_eq!(distribution.get(&HandRank::FullHouse), Some(&1.0));
+    }
+
+    #[test] fn a_pat_straight_stands_pat() {
+        let (choice, chance) = best_draw(&parse_hand("9S 8D 7C 6H 5S"), 1);
+        assert_eq!(choice.kept().cards().len(), 5);
+        assert!(chance > 0.9);
+    }
+
+    #[test] fn a_pair_keeps_the_pair() {
+        let (choice, _) = best_draw(&parse_hand("KS KD 7C 4H 2S"), 1);
+        assert_eq!(choice.kept().cards().iter().filter(|card| card.value() == &Value::King).count(), 2);
+    }
+}
@@ -0,0 +1,185 @@
+use hand::Hand;
+use arena::{Arena, ArenaReport};
+use calculations::{enumerated_equities, sampled_equities, SAMPLES};
+use history::HandHistory;
+use preflop::PreflopMatrix;
+use range::HandClass;
+use table::{Street, Action};
+
+use std::iter::repeat;
+use std::rand::Rng;
+
+/// Plays each of `deals` decks once for every way round the agents can sit, so each agent gets each
+/// seat's cards, and reports each agent's average over the rotations of a deck as one result.
+/// Luck in the cards mostly cancels out between rotations.
+pub fn duplicate(arena: &mut Arena, deals: usize) -> ArenaReport {
+    play_duplicate(arena, deals, false)
+}
+
+/// Like `duplicate`, with every result adjusted by `luck` as well. Boards for the luck estimate are
+/// dealt from the arena's generator, so a seeded arena gives the same report every time.
+pub fn duplicate_with_luck_adjustment(arena: &mut Arena, deals: usize) -> ArenaReport {
+    play_duplicate(arena, deals, true)
+}
+
+fn play_duplicate(arena: &mut Arena, deals: usize, adjust_for_luck: bool) -> ArenaReport {
+    let players = arena.names().len();
+    let big_blind = arena.big_blind() as f32;
+    let preflop = PreflopMatrix::exact();
+    let mut results = vec![];
+    for _ in range(0, deals) {
+        let deck = arena.shuffled_deck();
+        let mut totals = repeat(0.0).take(players).collect::<Vec<f32>>();
+        for rotation in range(0, players) {
+            let seating = range(0, players).map(|seat| (seat + rotation) % players).collect::<Vec<usize>>();
+            let (history, won) = arena.play_hand(&seating, deck.clone());
+            let luck = match adjust_for_luck {
+                true => luck(&history, &preflop, arena.rng()),
+                false => repeat(0.0).take(players).collect(),
+            };
+            for (seat, &agent) in seating.iter().enumerate() {
+                totals[agent] += won[agent] - luck[seat] / big_blind;
+            }
+        }
+        results.push(totals.iter().map(|total| total / players as f32).collect());
+    }
+    ArenaReport::new(arena.names(), results)
+}
+
+/// How many chips each seat won or lost to the cards rather than to their play, in the spirit of AIVAT.
+/// Every time cards are dealt, a seat's share of the pot as it stood is counted as having moved by
+/// however much the new cards moved their equity against the hands still in. The cards are just as
+/// likely to move it either way, so taking this away from a result leaves its average alone but
+/// takes out most of the noise.
+/// All hole cards have to be in the history, as they are for hands played in the arena. Heads-up
+/// preflop equities come from `preflop`, and any boards that need sampling are dealt from `rng`.
+pub fn luck<R: Rng>(history: &HandHistory, preflop: &PreflopMatrix, rng: &mut R) -> Vec<f32> {
+    let seats = history.seats.len();
+    let mut luck = repeat(0.0).take(seats).collect::<Vec<f32>>();
+    let dealt_in = range(0, seats).filter(|&seat| history.hole_cards[seat].is_some()).collect::<Vec<usize>>();
+    let board_dealt = history.board.len();
+    let streets = [Street::Preflop, Street::Flop, Street::Turn, Street::River];
+    let board_sizes = [0, 3, 4, 5];
+
+    // Equities known before the next cards come, and who they were worked out between.
+    let mut before: Option<(Vec<usize>, Vec<f32>)> = None;
+    for (index, &street) in streets.iter().enumerate() {
+        if board_sizes[index] > board_dealt {
+            break;
+        }
+        let live = dealt_in.iter().map(|&seat| seat)
+                   .filter(|&seat| !history.actions.iter().any(|action| {
+                       action.seat == seat && action.action == Action::Fold && is_before(action.street, street)
+                   }))
+                   .collect::<Vec<usize>>();
+        if live.len() < 2 {
+            break;
+        }
+        let pot = history.posts.iter().fold(0, |pot, post| pot + post.amount) +
+                  history.actions.iter().filter(|action| is_before(action.street, street)).fold(0, |pot, action| pot + action.amount);
+        let previous = match index {
+            0 => live.iter().map(|_| 1.0 / live.len() as f32).collect(),
+            _ => match before {
+                Some((ref was_live, ref known)) if *was_live == live => known.clone(),
+                _ => equities(history, &live, history.board_on(streets[index - 1]), preflop, rng),
+            },
+        };
+        let after = equities(history, &live, history.board_on(street), preflop, rng);
+        for ((&seat, was), now) in live.iter().zip(previous.iter()).zip(after.iter()) {
+            luck[seat] += pot as f32 * (*now - *was);
+        }
+        before = Some((live, after));
+    }
+    luck
+}
+
+/// Whether `first` comes before `second` in the hand.
+fn is_before(first: Street, second: Street) -> bool {
+    let order = |street: Street| match street {
+        Street::Preflop => 0,
+        Street::Flop => 1,
+        Street::Turn => 2,
+        Street::River => 3,
+        Street::Showdown => 4,
+    };
+    order(first) < order(second)
+}
+
+/// Each of `live`'s share of the pot against the others, with `board` out. Heads-up before the flop
+/// it comes from the exact preflop table, with two cards or fewer to come every board is dealt, and
+/// otherwise `rng` samples boards. The shares are all taken from the same boards, so they add up to one.
+fn equities<R: Rng>(history: &HandHistory, live: &Vec<usize>, board: Hand, preflop: &PreflopMatrix, rng: &mut R) -> Vec<f32> {
+    let pockets = live.iter().map(|&seat| history.hole_cards[seat].clone().unwrap()).collect::<Vec<Hand>>();
+    let to_come = 5 - board.cards().len();
+    if to_come == 5 && pockets.len() == 2 {
+        let (first, second) = (pockets[0].cards(), pockets[1].cards());
+        match (HandClass::of(&first[0], &first[1]), HandClass::of(&second[0], &second[1])) {
+            (Some(first), Some(second)) => {
+                let equity = preflop.lookup(first, second);
+                return vec![equity, 1.0 - equity];
+            },
+            _ => (),
+        }
+    }
+    match to_come {
+        0...2 => enumerated_equities(&pockets, board),
+        _ => sampled_equities(rng, SAMPLES, &pockets, board),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{duplicate, duplicate_with_luck_adjustment, luck};
+    use arena::{Agent, AlwaysCall, RaiseByEquity, Arena};
+    use preflop::PreflopMatrix;
+    use table::{Table, HandState, Action, stacked_deck};
+    use std::num::Float;
+    use std::rand::{SeedableRng, XorShiftRng};
+
+    fn calling_stations() -> Arena {
+        Arena::new(vec![Box::new(AlwaysCall) as Box<Agent>, Box::new(AlwaysCall) as Box<Agent>], 100, 1, 2)
+    }
+
+    #[test] fn mirrored_seats_cancel_out_for_identical_agents() {
+        let report = duplicate(&mut calling_stations(), 20);
+        assert_eq!(report.hands(), 20);
+        assert!(report.results(0).iter().all(|&result| result == 0.0));
+    }
+
+    #[test] fn luck_on_a_full_board_is_the_whole_swing() {
+        let mut hand = HandState::new(&Table::new(vec![100, 100], 1, 2),
+                                      stacked_deck("AS KD AH KC 2C 7D 8H 9C 3S JD 4C 5H")).ok().unwrap();
+        hand.act(Action::Call).ok().unwrap();
+        for _ in range(0, 7) {
+            hand.act(Action::Check).ok().unwrap();
+        }
+        let mut rng: XorShiftRng = SeedableRng::from_seed([1, 2, 3, 4]);
+        let luck = luck(&hand.history(), &PreflopMatrix::exact(), &mut rng);
+        // The aces are dealt about 82% of the 3 chips in the blinds, and the board brings them the rest
+        // of the 4 in the pot once the small blind's completed; completing it is play, not luck.
+        let aces = 0.8195;
+        assert!((luck[0] + luck[1]).abs() < 0.001);
+        assert!((luck[1] - (3.0 * (aces - 0.5) + 4.0 * (1.0 - aces))).abs() < 0.01);
+    }
+
+    #[test] fn adjusting_for_luck_narrows_the_interval() {
+        let game = || {
+            Arena::new(vec![Box::new(RaiseByEquity::new(0.6, [1, 2, 3, 4]).with_samples(50)) as Box<Agent>,
+                            Box::new(AlwaysCall) as Box<Agent>], 100, 1, 2)
+                .with_seed([3, 1, 4, 1])
+        };
+        let raw = duplicate(&mut game(), 30);
+        let adjusted = duplicate_with_luck_adjustment(&mut game(), 30);
+        assert!(adjusted.bb_per_100(0).1 < raw.bb_per_100(0).1);
+    }
+
+    #[test] fn luck_adjusted_reports_come_out_the_same_from_the_same_seed() {
+        let game = || {
+            Arena::new(vec![Box::new(AlwaysCall) as Box<Agent>, Box::new(AlwaysCall) as Box<Agent>, Box::new(AlwaysCall) as Box<Agent>],
+                       100, 1, 2).with_seed([2, 7, 1, 8])
+        };
+        let first = duplicate_with_luck_adjustment(&mut game(), 3);
+        let second = duplicate_with_luck_adjustment(&mut game(), 3);
+        assert_eq!(first.results(0), second.results(0));
+    }
+}
@@ -1,17 +1,20 @@
 extern crate test;
 
-use card::{Card, Value};
+use card::{Card, Value, Notation, Notated};
 use card::Value::*;
+use wild::WildRules;
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::iter::repeat;
 use std::str::FromStr;
 use std::ops::{Add, Sub};
 
 extern crate rust_combinatorics;
 use self::rust_combinatorics::combinatorics::binomial::Chooseable;
 
-#[derive(Debug, Eq, Ord, PartialEq, PartialOrd)]
-enum HandRank {
+#[derive(Debug, Eq, Ord, PartialEq, PartialOrd, Hash, Copy, Clone)]
+pub enum HandRank {
     HighCard,
     Pair,
     TwoPair,
@@ -21,23 +24,91 @@ enum HandRank {
     FullHouse,
     FourOfAKind,
     StraightFlush,
+    FiveOfAKind,
 }
 
 #[derive(Debug, Eq, Clone)]
 pub struct Hand {
     cards: Vec<Card>,
+    jokers: usize,
 }
 
 impl Hand {
+    /// Any jokers among `cards` are kept apart from the real cards, so they never get evaluated as one.
     pub fn new(cards: Vec<Card>) -> Hand {
-        Hand { cards: cards }
+        Hand::with_jokers(cards, 0)
     }
 
+    pub fn with_jokers(cards: Vec<Card>, jokers: usize) -> Hand {
+        let jokers = jokers + cards.iter().filter(|card| card.is_joker()).count();
+        Hand { cards: cards.into_iter().filter(|card| !card.is_joker()).collect(), jokers: jokers }
+    }
+
+    /// Every card in the hand, with any jokers last.
     pub fn cards(&self) -> Vec<Card> {
+        let mut cards = self.cards.clone();
+        cards.extend(repeat(Card::joker()).take(self.jokers));
+        cards
+    }
+
+    /// The real cards in the hand, leaving out jokers.
+    pub fn natural_cards(&self) -> Vec<Card> {
         self.cards.clone()
     }
 
+    pub fn jokers(&self) -> usize {
+        self.jokers
+    }
+
+    pub fn notated(&self, notation: Notation) -> Notated<Hand> {
+        Notated { item: self, notation: notation }
+    }
+
+    /// The best five cards, with any jokers played as whatever card suits them best.
+    pub fn best_five(&self) -> Hand {
+        if self.jokers > 0 {
+            return WildRules::jokers_wild().best_hand(self);
+        }
+        match self.cards.len() > 5 {
+            true => self.cards.clone().choose(5).map(|cards| Hand::new(cards)).max().unwrap(),
+            false => self.clone(),
+        }
+    }
+
+    pub fn rank(&self) -> HandRank {
+        self.best_five().categorize()
+    }
+
+    /// The values that decide ties between hands of the same rank, most important first.
+    pub fn ranked_values(&self) -> Vec<Value> {
+        self.best_five().most_common_values().into_iter().map(|&value| value).collect()
+    }
+
+    /// A description like "Two Pair, Kings and Fives".
+    pub fn describe(&self) -> String {
+        let values = self.ranked_values();
+        if values.is_empty() {
+            return "No Cards".to_string();
+        }
+        match self.rank() {
+            HandRank::HighCard => format!("High Card, {}", values[0].name()),
+            HandRank::Pair => format!("Pair of {}", values[0].plural_name()),
+            HandRank::TwoPair => format!("Two Pair, {} and {}", values[0].plural_name(), values[1].plural_name()),
+            HandRank::ThreeOfAKind => format!("Three of a Kind, {}", values[0].plural_name()),
+            HandRank::Straight => format!("Straight, {} high", values[0].name()),
+            HandRank::Flush => format!("Flush, {} high", values[0].name()),
+            HandRank::FullHouse => format!("Full House, {} full of {}", values[0].plural_name(), values[1].plural_name()),
+            HandRank::FourOfAKind => format!("Four of a Kind, {}", values[0].plural_name()),
+            HandRank::StraightFlush if values[0] == Ace => "Royal Flush".to_string(),
+            HandRank::StraightFlush => format!("Straight Flush, {} high", values[0].name()),
+            HandRank::FiveOfAKind => format!("Five of a Kind, {}", values[0].plural_name()),
+        }
+    }
+
     fn categorize(&self) -> HandRank {
+        if self.is_five_of_a_kind() {
+            return HandRank::FiveOfAKind;
+        }
         match (self.is_straight(), self.is_flush()) {
             (true, true) => HandRank::StraightFlush,
             (true, false) => HandRank::Straight,
@@ -93,7 +164,14 @@ impl Hand {
         }
     }
 
+    fn is_five_of_a_kind(&self) -> bool {
+        self.cards.len() == 5 && self.cards.iter().all(|card| card.value() == self.cards[0].value())
+    }
+
     fn is_flush(&self) -> bool {
+        if self.cards.len() < 5 {
+            return false;
+        }
         match self.cards.get(0) {
             Some(card) => {
                 let first_suit = card.suit();
@@ -136,11 +214,19 @@ impl Hand {
 impl FromStr for Hand {
     type Err = HandParseErr;
     fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
-        let cards: Vec<Option<Card>> = s.split_str(" ")
+        // Cards written out in long form have spaces in them, so they're split by commas instead.
+        let separator = match s.contains(",") || s.contains(" of ") {
+            true => ",",
+            false => " ",
+        };
+        let card_strings = s.split_str(separator)
+            .map(|card_string| card_string.trim())
             .filter(|&card_string| match card_string {
                 "" => false,
                 _ => true,
             })
+            .collect::<Vec<&str>>();
+        let cards: Vec<Option<Card>> = card_strings.into_iter()
             .map(|card_string| {
                 match card_string.parse() {
                     Ok(result) => Some(result),
@@ -156,10 +242,29 @@ impl FromStr for Hand {
     }
 }
 
-enum HandParseErr {
+pub enum HandParseErr {
     Err
 }
 
+impl<'a> fmt::Display for Notated<'a, Hand> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let separator = match self.notation {
+            Notation::Long => ", ",
+            _ => " ",
+        };
+        let written = self.item.cards().iter()
+            .map(|card| card.notated(self.notation).to_string())
+            .collect::<Vec<String>>();
+        write!(f, "{}", written.connect(separator))
+    }
+}
+
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.notated(Notation::Native))
+    }
+}
+
 impl PartialEq for Hand {
     fn eq(&self, other: &Self) -> bool {
         match self.partial_cmp(other) {
@@ -171,13 +276,8 @@ impl PartialEq for Hand {
 
 impl PartialOrd for Hand {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self.cards.len() > 5 {
-            true => {
-                let my_best_hand = self.cards.clone().choose(5).map(|cards| Hand::new(cards)).max();
-                let other_best_hand = other.cards.clone().choose(5).map(|cards| Hand::new(cards)).max();
-
-                my_best_hand.partial_cmp(&other_best_hand)
-            },
+        match self.cards.len() > 5 || other.cards.len() > 5 || self.jokers > 0 || other.jokers > 0 {
+            true => self.best_five().partial_cmp(&other.best_five()),
             false => {
                 match self.categorize().partial_cmp(&other.categorize()) {
                     Some(Ordering::Equal) => {
@@ -207,25 +307,31 @@ impl Add for Hand {
         new_cards.append(&mut other.cards);
         new_cards.sort();
         new_cards.dedup();
-        Hand::new(new_cards)
+        Hand::with_jokers(new_cards, self.jokers + other.jokers)
     }
 }
 
 impl Sub for Hand {
     type Output = Hand;
     fn sub(self, other: Self) -> Self {
-        Hand::new(self.cards
-                      .iter()
-                      .filter(|&card| other.cards.iter().all(|other_card| card != other_card))
-                      .map(|&card| card)
-                      .collect()
+        let jokers = match self.jokers > other.jokers {
+            true => self.jokers - other.jokers,
+            false => 0,
+        };
+        Hand::with_jokers(self.cards
+                              .iter()
+                              .filter(|&card| other.cards.iter().all(|other_card| card != other_card))
+                              .map(|&card| card)
+                              .collect(),
+                          jokers
         )
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Hand;
+    use super::{Hand, HandRank};
+    use card::Notation;
 
     fn parse_hand(s: &str) -> Hand {
         s.parse().ok().unwrap()
@@ -417,6 +523,70 @@ mod tests {
         assert!(parse_hand("").cards() == vec![]);
     }
 
+    #[test] fn fewer_than_five_suited_cards_are_not_a_flush() {
+        assert_hand_beats(parse_hand("2S 2H"), parse_hand("AH KH"));
+    }
+
+    #[test] fn rank_uses_the_best_five_cards() {
+        assert_eq!(parse_hand("2H 3H 0H 5H 6H 9D KS").rank(), HandRank::Flush);
+    }
+
+    #[test] fn best_five_drops_the_worst_cards() {
+        assert!(parse_hand("KS 9D 2H 3H 0H 5H 6H").best_five().cards() == parse_hand("2H 3H 0H 5H 6H").cards());
+    }
+
+    #[test] fn can_parse_jokers() {
+        let hand = parse_hand("AS XX 0H xx");
+        assert_eq!(hand.jokers(), 2);
+        assert_eq!(hand.natural_cards().len(), 2);
+        assert_eq!(hand.cards().len(), 4);
+        assert!(hand.cards()[3].is_joker());
+    }
+
+    #[test] fn hands_are_written_in_each_notation() {
+        let hand = parse_hand("AS 0H XX");
+        assert_eq!(hand.to_string(), "AS 0H XX");
+        assert_eq!(hand.notated(Notation::Standard).to_string(), "As Th XX");
+        assert_eq!(hand.notated(Notation::Unicode).to_string(), "A♠ T♥ XX");
+        assert_eq!(hand.notated(Notation::Long).to_string(), "Ace of Spades, Ten of Hearts, Joker");
+    }
+
+    #[test] fn hands_parse_back_from_each_notation() {
+        let hand = parse_hand("AS 0H 2C 7D XX");
+        for &notation in [Notation::Native, Notation::Standard, Notation::Unicode, Notation::Long, Notation::FourColor].iter() {
+            let parsed = parse_hand(hand.notated(notation).to_string().as_slice());
+            assert!(parsed.cards() == hand.cards());
+            assert_eq!(parsed.jokers(), 1);
+        }
+    }
+
+    #[test] fn jokers_survive_rebuilding_a_hand_from_its_cards() {
+        let hand = parse_hand("AS AH XX");
+        assert_eq!(Hand::new(hand.cards()).jokers(), 1);
+        assert_hand_beats(Hand::new(hand.cards()), parse_hand("KS KH KD"));
+    }
+
+    #[test] fn jokers_play_as_the_best_card() {
+        assert_hand_beats(parse_hand("AS AH AD XX 2C"), parse_hand("KS KH KD KC 2C"));
+    }
+
+    #[test] fn four_of_a_kind_and_a_joker_is_five_of_a_kind() {
+        assert_eq!(parse_hand("9S 9H 9D 9C XX").rank(), HandRank::FiveOfAKind);
+        assert_hand_beats(parse_hand("2S 2H 2D 2C XX"), parse_hand("0S JS QS KS AS"));
+    }
+
+    #[test] fn adding_and_subtracting_hands_keeps_jokers() {
+        assert_eq!((parse_hand("AS XX") + parse_hand("XX")).jokers(), 2);
+        assert_eq!((parse_hand("AS XX") - parse_hand("XX XX")).jokers(), 0);
+    }
+
+    #[test] fn hands_describe_themselves() {
+        assert_eq!(parse_hand("KS KH 5D 5C 2H").describe(), "Two Pair, Kings and Fives");
+        assert_eq!(parse_hand("0S 0H 0D KC KH").describe(), "Full House, Tens full of Kings");
+        assert_eq!(parse_hand("AH 2S 3H 4H 5D").describe(), "Straight, Five high");
+        assert_eq!(parse_hand("0S JS QS KS AS 2D 3C").describe(), "Royal Flush");
+    }
+
     #[test] fn can_compare_empty_hands() {
         assert!(parse_hand("") == parse_hand(""));
     }
@@ -0,0 +1,363 @@
+use card::Card;
+use hand::Hand;
+use table::{Street, Action, Limit};
+
+pub mod phh;
+pub mod pokerstars;
+
+/// Why a hand history written elsewhere couldn't be read, with the text that didn't make sense.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum HistoryParseErr {
+    MissingHeader,
+    BadSeat(String),
+    UnknownPlayer(String),
+    BadCards(String),
+    BadAmount(String),
+    BadLine(String),
+    MissingField(String),
+    Unsupported(String),
+}
+
+/// A player sitting down for the hand, with the chips they started it with.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SeatRecord {
+    pub name: String,
+    pub stack: u32,
+}
+
+impl SeatRecord {
+    pub fn new(name: String, stack: u32) -> SeatRecord {
+        SeatRecord { name: name, stack: stack }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Blind {
+    Ante,
+    SmallBlind,
+    BigBlind,
+}
+
+/// Chips a seat had to put in before the cards were dealt.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Post {
+    pub seat: usize,
+    pub blind: Blind,
+    pub amount: u32,
+}
+
+impl Post {
+    pub fn new(seat: usize, blind: Blind, amount: u32) -> Post {
+        Post { seat: seat, blind: blind, amount: amount }
+    }
+}
+
+/// An action and the chips it put in the pot.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct ActionRecord {
+    pub street: Street,
+    pub seat: usize,
+    pub action: Action,
+    pub amount: u32,
+}
+
+impl ActionRecord {
+    pub fn new(street: Street, seat: usize, action: Action, amount: u32) -> ActionRecord {
+        ActionRecord { street: street, seat: seat, action: action, amount: amount }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ShowdownRecord {
+    pub seat: usize,
+    pub hole_cards: Hand,
+    pub best_five: Hand,
+    pub description: String,
+}
+
+impl ShowdownRecord {
+    /// `hand` is everything the seat plays with, hole cards and board together.
+    pub fn new(seat: usize, hole_cards: Hand, hand: &Hand) -> ShowdownRecord {
+        ShowdownRecord {
+            seat: seat,
+            hole_cards: hole_cards,
+            best_five: hand.best_five(),
+            description: hand.describe(),
+        }
+    }
+}
+
+/// Everything that happened in one hand. Seats are referred to by their index in `seats`.
+#[derive(Debug, Clone)]
+pub struct HandHistory {
+    pub seats: Vec<SeatRecord>,
+    pub button: usize,
+    pub small_blind: u32,
+    pub big_blind: u32,
+    pub ante: u32,
+    pub limit: Limit,
+    /// `None` for seats that weren't dealt in or whose cards were never seen.
+    pub hole_cards: Vec<Option<Hand>>,
+    pub posts: Vec<Post>,
+    pub actions: Vec<ActionRecord>,
+    pub board: Vec<Card>,
+    pub showdown: Vec<ShowdownRecord>,
+    pub winnings: Vec<u32>,
+}
+
+impl HandHistory {
+    pub fn with_names(mut self, names: Vec<String>) -> HandHistory {
+        for (seat, name) in self.seats.iter_mut().zip(names.into_iter()) {
+            seat.name = name;
+        }
+        self
+    }
+
+    /// The board as it was on `street`.
+    pub fn board_on(&self, street: Street) -> Hand {
+        let cards = match street {
+            Street::Preflop => 0,
+            Street::Flop => 3,
+            Street::Turn => 4,
+            _ => 5,
+        };
+        Hand::new(self.board.iter().take(cards).map(|&card| card).collect())
+    }
+
+    pub fn total_pot(&self) -> u32 {
+        self.posts.iter().fold(0, |pot, post| pot + post.amount) +
+            self.actions.iter().fold(0, |pot, action| pot + action.amount)
+    }
+
+    pub fn to_json(&self) -> String {
+        let seats = self.seats.iter()
+                        .map(|seat| format!("{{\"name\": {}, \"stack\": {}}}", json_string(&seat.name), seat.stack))
+                        .collect::<Vec<String>>();
+        let hole_cards = self.hole_cards.iter()
+                             .map(|cards| match *cards {
+                                 Some(ref cards) => json_cards(&cards.cards()),
+                                 None => "null".to_string(),
+                             })
+                             .collect::<Vec<String>>();
+        let posts = self.posts.iter()
+                        .map(|post| format!("{{\"seat\": {}, \"blind\": \"{}\", \"amount\": {}}}",
+                                            post.seat, blind_name(post.blind), post.amount))
+                        .collect::<Vec<String>>();
+        let actions = self.actions.iter()
+                          .map(|action| {
+                              let to = match action.action {
+                                  Action::Bet(to) | Action::Raise(to) => format!(", \"to\": {}", to),
+                                  _ => "".to_string(),
+                              };
+                              format!("{{\"street\": \"{}\", \"seat\": {}, \"action\": \"{}\"{}, \"amount\": {}}}",
+                                      street_name(action.street), action.seat, action_name(action.action), to, action.amount)
+                          })
+                          .collect::<Vec<String>>();
+        let showdown = self.showdown.iter()
+                           .map(|shown| format!("{{\"seat\": {}, \"hole_cards\": {}, \"best_five\": {}, \"description\": {}}}",
+                                                shown.seat, json_cards(&shown.hole_cards.cards()),
+                                                json_cards(&shown.best_five.cards()), json_string(&shown.description)))
+                           .collect::<Vec<String>>();
+        let winnings = self.winnings.iter().map(|won| won.to_string()).collect::<Vec<String>>();
+
+        format!("{{\"seats\": [{}], \"button\": {}, \"small_blind\": {}, \"big_blind\": {}, \"ante\": {}, \"limit\": {}, \
+                 \"hole_cards\": [{}], \"posts\": [{}], \"actions\": [{}], \
+                 \"board\": {{\"flop\": {}, \"turn\": {}, \"river\": {}}}, \"showdown\": [{}], \"winnings\": [{}]}}",
+                seats.connect(", "), self.button, self.small_blind, self.big_blind, self.ante, json_limit(self.limit),
+                hole_cards.connect(", "), posts.connect(", "), actions.connect(", "),
+                json_cards(&self.board.iter().take(3).map(|&card| card).collect()),
+                json_cards(&self.board.iter().skip(3).take(1).map(|&card| card).collect()),
+                json_cards(&self.board.iter().skip(4).take(1).map(|&card| card).collect()),
+                showdown.connect(", "), winnings.connect(", "))
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![];
+        lines.push(format!("Hold'em {} ({}/{}) - Button: {}", limit_name(self.limit), self.small_blind, self.big_blind,
+                           self.seats[self.button].name));
+        for (index, seat) in self.seats.iter().enumerate() {
+            lines.push(format!("Seat {}: {} ({} in chips)", index + 1, seat.name, seat.stack));
+        }
+        for post in self.posts.iter() {
+            lines.push(format!("{}: posts {} {}", self.seats[post.seat].name, blind_name(post.blind), post.amount));
+        }
+        lines.push("*** HOLE CARDS ***".to_string());
+        for (index, cards) in self.hole_cards.iter().enumerate() {
+            match *cards {
+                Some(ref cards) => lines.push(format!("Dealt to {} [{}]", self.seats[index].name, cards_string(&cards.cards()))),
+                None => (),
+            }
+        }
+        let mut street = Street::Preflop;
+        for action in self.actions.iter() {
+            if action.street != street {
+                street = action.street;
+                lines.push(self.street_line(street));
+            }
+            lines.push(format!("{}: {}", self.seats[action.seat].name, action_text(action)));
+        }
+        // Streets nobody could bet on, with everyone all in.
+        for &later in [Street::Flop, Street::Turn, Street::River].iter() {
+            if (later as usize) > (street as usize) && self.board_on(later).cards().len() > self.board_on(street).cards().len() {
+                street = later;
+                lines.push(self.street_line(street));
+            }
+        }
+        if !self.showdown.is_empty() {
+            lines.push("*** SHOWDOWN ***".to_string());
+            for shown in self.showdown.iter() {
+                lines.push(format!("{}: shows [{}] ({})", self.seats[shown.seat].name,
+                                   cards_string(&shown.hole_cards.cards()), shown.description));
+            }
+        }
+        lines.push("*** SUMMARY ***".to_string());
+        lines.push(format!("Total pot {}", self.total_pot()));
+        if !self.board.is_empty() {
+            lines.push(format!("Board [{}]", cards_string(&self.board)));
+        }
+        for (index, &won) in self.winnings.iter().enumerate().filter(|&(_, &won)| won > 0) {
+            lines.push(format!("{} won {}", self.seats[index].name, won));
+        }
+        lines.connect("\n")
+    }
+
+    fn street_line(&self, street: Street) -> String {
+        match street {
+            Street::Flop => format!("*** FLOP *** [{}]", cards_string(&self.board_on(Street::Flop).cards())),
+            Street::Turn => format!("*** TURN *** [{}] [{}]", cards_string(&self.board_on(Street::Flop).cards()),
+                                    card_string(&self.board[3])),
+            Street::River => format!("*** RIVER *** [{}] [{}]", cards_string(&self.board_on(Street::Turn).cards()),
+                                     card_string(&self.board[4])),
+            _ => "".to_string(),
+        }
+    }
+}
+
+fn card_string(card: &Card) -> String {
+    format!("{}{}", card.value().to_char(), card.suit().to_char())
+}
+
+fn cards_string(cards: &Vec<Card>) -> String {
+    cards.iter().map(|card| card_string(card)).collect::<Vec<String>>().connect(" ")
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = "\"".to_string();
+    for char in s.chars() {
+        match char {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            char if (char as u32) < 0x20 => escaped.push_str(format!("\\u{:04x}", char as u32).as_slice()),
+            char => escaped.push(char),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn json_cards(cards: &Vec<Card>) -> String {
+    format!("[{}]", cards.iter().map(|card| format!("\"{}\"", card_string(card))).collect::<Vec<String>>().connect(", "))
+}
+
+fn json_limit(limit: Limit) -> String {
+    match limit {
+        Limit::NoLimit => "{\"type\": \"no-limit\"}".to_string(),
+        Limit::PotLimit => "{\"type\": \"pot-limit\"}".to_string(),
+        Limit::FixedLimit { small_bet, big_bet, cap } =>
+            format!("{{\"type\": \"fixed-limit\", \"small_bet\": {}, \"big_bet\": {}, \"cap\": {}}}", small_bet, big_bet, cap),
+    }
+}
+
+fn limit_name(limit: Limit) -> &'static str {
+    match limit {
+        Limit::NoLimit => "No Limit",
+        Limit::PotLimit => "Pot Limit",
+        Limit::FixedLimit { .. } => "Limit",
+    }
+}
+
+fn blind_name(blind: Blind) -> &'static str {
+    match blind {
+        Blind::Ante => "the ante",
+        Blind::SmallBlind => "small blind",
+        Blind::BigBlind => "big blind",
+    }
+}
+
+fn street_name(street: Street) -> &'static str {
+    match street {
+        Street::Preflop => "preflop",
+        Street::Flop => "flop",
+        Street::Turn => "turn",
+        Street::River => "river",
+        Street::Showdown => "showdown",
+    }
+}
+
+fn action_name(action: Action) -> &'static str {
+    match action {
+        Action::Fold => "fold",
+        Action::Check => "check",
+        Action::Call => "call",
+        Action::Bet(_) => "bet",
+        Action::Raise(_) => "raise",
+    }
+}
+
+fn action_text(record: &ActionRecord) -> String {
+    match record.action {
+        Action::Fold => "folds".to_string(),
+        Action::Check => "checks".to_string(),
+        Action::Call => format!("calls {}", record.amount),
+        Action::Bet(to) => format!("bets {}", to),
+        Action::Raise(to) => format!("raises to {}", to),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use table::{Street, played_hand};
+
+    #[test] fn history_records_posts_and_actions() {
+        let history = played_hand().history();
+        assert_eq!(history.posts.len(), 2);
+        assert_eq!(history.actions.len(), 8);
+        assert_eq!(history.actions[1].amount, 4);
+        assert_eq!(history.actions[2].street, Street::Flop);
+        assert_eq!(history.total_pot(), 32);
+    }
+
+    #[test] fn history_records_the_showdown_and_winnings() {
+        let history = played_hand().history();
+        assert_eq!(history.showdown.len(), 2);
+        assert_eq!(history.showdown[0].description, "Pair of Kings");
+        assert_eq!(history.showdown[1].description, "Pair of Aces");
+        assert_eq!(history.winnings, vec![0, 32]);
+    }
+
+    #[test] fn board_on_shows_the_cards_out_by_each_street() {
+        let history = played_hand().history();
+        assert_eq!(history.board_on(Street::Preflop).cards().len(), 0);
+        assert_eq!(history.board_on(Street::Turn).cards().len(), 4);
+    }
+
+    #[test] fn history_serializes_to_json() {
+        let json = played_hand().history().with_names(vec!["Alice".to_string(), "Bob \"B\"".to_string()]).to_json();
+        assert!(json.starts_with("{\"seats\": [{\"name\": \"Alice\", \"stack\": 100}, {\"name\": \"Bob \\\"B\\\"\", \"stack\": 100}]"));
+        assert!(json.contains("\"action\": \"raise\", \"to\": 6, \"amount\": 5"));
+        assert!(json.contains("\"board\": {\"flop\": [\"7D\", \"8H\", \"9C\"], \"turn\": [\"JD\"], \"river\": [\"5H\"]}"));
+        assert!(json.ends_with("\"winnings\": [0, 32]}"));
+    }
+
+    #[test] fn history_serializes_to_text() {
+        let text = played_hand().history().to_text();
+        let lines = text.lines().collect::<Vec<&str>>();
+        assert_eq!(lines[0], "Hold'em No Limit (1/2) - Button: Seat 1");
+        assert!(lines.contains(&"Seat 1: raises to 6"));
+        assert!(lines.contains(&"*** FLOP *** [7D 8H 9C]"));
+        assert!(lines.contains(&"*** RIVER *** [7D 8H 9C JD] [5H]"));
+        assert!(lines.contains(&"Seat 2: shows [AS AH] (Pair of Aces)"));
+        assert!(lines.contains(&"Seat 2 won 32"));
+    }
+}
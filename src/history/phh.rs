@@ -0,0 +1,615 @@
+use card::{Card, Notation};
+use hand::Hand;
+use pot::{build_pots, payouts};
+use table::{Street, Action, Limit};
+use super::{HandHistory, HistoryParseErr, SeatRecord, Post, Blind, ActionRecord, ShowdownRecord, json_string};
+
+use std::char;
+use std::cmp::{min, max};
+use std::iter::repeat;
+
+/// Writes the hand in the Poker Hand History (PHH) format, as no-limit ("NT") or fixed-limit ("FT") hold'em.
+/// Players are listed starting left of the button, so the button comes last, as the format expects.
+/// PHH has no pot-limit hold'em variant, so pot-limit hands give `None`.
+pub fn write(history: &HandHistory) -> Option<String> {
+    let players = history.seats.len();
+    let order = range(0, players).map(|index| (history.button + 1 + index) % players).collect::<Vec<usize>>();
+    let player_number = |seat: usize| (seat + players - history.button - 1) % players + 1;
+
+    let mut lines = vec![];
+    match history.limit {
+        Limit::NoLimit => lines.push("variant = \"NT\"".to_string()),
+        Limit::FixedLimit { .. } => lines.push("variant = \"FT\"".to_string()),
+        Limit::PotLimit => return None,
+    }
+    lines.push("ante_trimming_status = false".to_string());
+    let posted = |blinds: &[Blind]| {
+        order.iter()
+             .map(|&seat| {
+                 history.posts.iter()
+                        .filter(|post| post.seat == seat && blinds.contains(&post.blind))
+                        .fold(0, |posted, post| posted + post.amount)
+             })
+             .collect::<Vec<u32>>()
+    };
+    lines.push(format!("antes = {}", integer_array(&posted(&[Blind::Ante]))));
+    lines.push(format!("blinds_or_straddles = {}", integer_array(&posted(&[Blind::SmallBlind, Blind::BigBlind]))));
+    match history.limit {
+        Limit::FixedLimit { small_bet, big_bet, .. } => {
+            lines.push(format!("small_bet = {}", small_bet));
+            lines.push(format!("big_bet = {}", big_bet));
+        },
+        _ => lines.push(format!("min_bet = {}", history.big_blind)),
+    }
+    lines.push(format!("starting_stacks = {}",
+                       integer_array(&order.iter().map(|&seat| history.seats[seat].stack).collect())));
+
+    let mut actions = vec![];
+    for &seat in order.iter() {
+        let cards = match history.hole_cards[seat] {
+            Some(ref cards) => phh_cards(&cards.cards()),
+            None => "????".to_string(),
+        };
+        actions.push(format!("d dh p{} {}", player_number(seat), cards));
+    }
+    let mut street = Street::Preflop;
+    for action in history.actions.iter() {
+        while (street as usize) < (action.street as usize) {
+            street = next_street(street);
+            actions.push(board_line(history, street));
+        }
+        actions.push(match action.action {
+            Action::Fold => format!("p{} f", player_number(action.seat)),
+            Action::Check | Action::Call => format!("p{} cc", player_number(action.seat)),
+            Action::Bet(to) | Action::Raise(to) => format!("p{} cbr {}", player_number(action.seat), to),
+        });
+    }
+    // Streets dealt with nobody left to bet.
+    while (street as usize) < (Street::River as usize) && history.board.len() > cards_before(next_street(street)) {
+        street = next_street(street);
+        actions.push(board_line(history, street));
+    }
+    for shown in history.showdown.iter() {
+        actions.push(format!("p{} sm {}", player_number(shown.seat), phh_cards(&shown.hole_cards.cards())));
+    }
+    lines.push(format!("actions = [\n{}\n]",
+                       actions.iter().map(|action| format!("  {}", json_string(action))).collect::<Vec<String>>().connect(",\n")));
+    lines.push(format!("players = [{}]",
+                       order.iter().map(|&seat| json_string(&history.seats[seat].name)).collect::<Vec<String>>().connect(", ")));
+
+    let finishing = order.iter()
+                         .map(|&seat| {
+                             let put_in = history.posts.iter().filter(|post| post.seat == seat).fold(0, |sum, post| sum + post.amount) +
+                                 history.actions.iter().filter(|action| action.seat == seat).fold(0, |sum, action| sum + action.amount);
+                             history.seats[seat].stack - put_in + history.winnings[seat]
+                         })
+                         .collect();
+    lines.push(format!("finishing_stacks = {}", integer_array(&finishing)));
+    Some(lines.connect("\n"))
+}
+
+/// Reads a no-limit or fixed-limit hold'em hand from a PHH file.
+/// Seats are numbered from 0 in the file's player order, which makes the last player the button.
+/// Without `finishing_stacks`, winnings are worked out from the hands that were dealt face up or shown.
+pub fn parse(text: &str) -> Result<HandHistory, HistoryParseErr> {
+    let fields = try!(parse_toml(text));
+    let stacks = try!(integers(&fields, "starting_stacks"));
+    let players = stacks.len();
+    let zeros = repeat(0).take(players).collect::<Vec<u32>>();
+    let antes = match field(&fields, "antes") {
+        Some(_) => try!(integers(&fields, "antes")),
+        None => zeros.clone(),
+    };
+    let blinds = try!(integers(&fields, "blinds_or_straddles"));
+    let limit = match try!(text_field(&fields, "variant")).as_slice() {
+        "NT" => Limit::NoLimit,
+        "FT" => Limit::FixedLimit {
+            small_bet: try!(integer(&fields, "small_bet")),
+            big_bet: try!(integer(&fields, "big_bet")),
+            cap: 4,
+        },
+        other => return Err(HistoryParseErr::Unsupported(other.to_string())),
+    };
+    let names = match field(&fields, "players") {
+        Some(_) => try!(texts(&fields, "players")),
+        None => range(0, players).map(|index| format!("p{}", index + 1)).collect(),
+    };
+    if antes.len() != players || blinds.len() != players || names.len() != players {
+        return Err(HistoryParseErr::BadSeat("players, stacks, antes and blinds don't match up".to_string()));
+    }
+
+    let small_blind = blinds.iter().filter(|&&blind| blind > 0).min().map(|&blind| blind).unwrap_or(0);
+    let big_blind = blinds.iter().max().map(|&blind| blind).unwrap_or(0);
+    let mut history = HandHistory {
+        seats: names.into_iter().zip(stacks.iter()).map(|(name, &stack)| SeatRecord::new(name, stack)).collect(),
+        button: players - 1,
+        small_blind: small_blind,
+        big_blind: big_blind,
+        ante: antes.iter().max().map(|&ante| ante).unwrap_or(0),
+        limit: limit,
+        hole_cards: repeat(None).take(players).collect(),
+        posts: vec![],
+        actions: vec![],
+        board: vec![],
+        showdown: vec![],
+        winnings: zeros.clone(),
+    };
+    let mut remaining = stacks.clone();
+    for (player, &ante) in antes.iter().enumerate().filter(|&(_, &ante)| ante > 0) {
+        history.posts.push(Post::new(player, Blind::Ante, ante));
+        remaining[player] -= min(ante, remaining[player]);
+    }
+    for &big in [false, true].iter() {
+        for (player, &blind) in blinds.iter().enumerate().filter(|&(_, &blind)| blind > 0) {
+            if (blind == big_blind) == big {
+                let kind = match big {
+                    true => Blind::BigBlind,
+                    false => Blind::SmallBlind,
+                };
+                history.posts.push(Post::new(player, kind, blind));
+                remaining[player] -= min(blind, remaining[player]);
+            }
+        }
+    }
+
+    let mut street = Street::Preflop;
+    let mut bets = blinds.clone();
+    let mut current_bet = big_blind;
+    let mut folded = repeat(false).take(players).collect::<Vec<bool>>();
+    for entry in try!(texts(&fields, "actions")).iter() {
+        let entry = match entry.find('#') {
+            Some(end) => &entry[..end],
+            None => entry.as_slice(),
+        }.trim();
+        let bad_line = || HistoryParseErr::BadLine(entry.to_string());
+        let words = entry.split(' ').filter(|word| !word.is_empty()).collect::<Vec<&str>>();
+        if words.len() < 2 {
+            return Err(bad_line());
+        }
+        if words[0] == "d" {
+            match words[1] {
+                "dh" if words.len() == 4 => {
+                    let player = try!(player_index(words[2], players).ok_or(bad_line()));
+                    if !words[3].contains("?") {
+                        history.hole_cards[player] = Some(Hand::new(try!(parse_cards(words[3]))));
+                    }
+                },
+                "db" if words.len() == 3 => {
+                    street = next_street(street);
+                    history.board.extend(try!(parse_cards(words[2])).into_iter());
+                    bets = zeros.clone();
+                    current_bet = 0;
+                },
+                _ => return Err(bad_line()),
+            }
+            continue;
+        }
+        let player = try!(player_index(words[0], players).ok_or(bad_line()));
+        match words[1] {
+            "f" => {
+                folded[player] = true;
+                history.actions.push(ActionRecord::new(street, player, Action::Fold, 0));
+            },
+            "cc" => {
+                let amount = min(current_bet - min(current_bet, bets[player]), remaining[player]);
+                let action = match amount {
+                    0 => Action::Check,
+                    _ => Action::Call,
+                };
+                bets[player] += amount;
+                remaining[player] -= amount;
+                history.actions.push(ActionRecord::new(street, player, action, amount));
+            },
+            "cbr" if words.len() == 3 => {
+                let to = try!(words[2].parse::<u32>().ok().ok_or(bad_line()));
+                if to < bets[player] || to - bets[player] > remaining[player] {
+                    return Err(HistoryParseErr::BadAmount(entry.to_string()));
+                }
+                let amount = to - bets[player];
+                let action = match current_bet {
+                    0 => Action::Bet(to),
+                    _ => Action::Raise(to),
+                };
+                bets[player] = to;
+                remaining[player] -= amount;
+                current_bet = max(current_bet, to);
+                history.actions.push(ActionRecord::new(street, player, action, amount));
+            },
+            "sm" => {
+                if words.len() == 3 && words[2] != "-" {
+                    let hole_cards = Hand::new(try!(parse_cards(words[2])));
+                    let hand = hole_cards.clone() + Hand::new(history.board.clone());
+                    history.showdown.push(ShowdownRecord::new(player, hole_cards.clone(), &hand));
+                    history.hole_cards[player] = Some(hole_cards);
+                }
+            },
+            // Draws, stud bring-ins and the like belong to variants the table doesn't play.
+            _ => return Err(HistoryParseErr::Unsupported(entry.to_string())),
+        }
+    }
+
+    history.winnings = match field(&fields, "finishing_stacks") {
+        Some(_) => {
+            let finishing = try!(integers(&fields, "finishing_stacks"));
+            if finishing.len() != players {
+                return Err(HistoryParseErr::BadSeat("finishing_stacks".to_string()));
+            }
+            range(0, players).map(|player| finishing[player] - min(finishing[player], remaining[player])).collect()
+        },
+        None => {
+            let contributions = range(0, players).map(|player| stacks[player] - remaining[player]).collect();
+            let hands = range(0, players)
+                        .map(|player| match (folded[player], &history.hole_cards[player]) {
+                            (false, &Some(ref cards)) => Some(cards.clone() + Hand::new(history.board.clone())),
+                            _ => None,
+                        })
+                        .collect();
+            payouts(&build_pots(&contributions, &folded), &hands, 0)
+        },
+    };
+    Ok(history)
+}
+
+fn next_street(street: Street) -> Street {
+    match street {
+        Street::Preflop => Street::Flop,
+        Street::Flop => Street::Turn,
+        Street::Turn => Street::River,
+        _ => Street::Showdown,
+    }
+}
+
+fn cards_before(street: Street) -> usize {
+    match street {
+        Street::Preflop | Street::Flop => 0,
+        Street::Turn => 3,
+        _ => 4,
+    }
+}
+
+fn board_line(history: &HandHistory, street: Street) -> String {
+    let dealt = match street {
+        Street::Flop => 3,
+        _ => 1,
+    };
+    let cards = history.board.iter().skip(cards_before(street)).take(dealt).map(|&card| card).collect();
+    format!("d db {}", phh_cards(&cards))
+}
+
+/// "p3" is the third player, seat 2.
+fn player_index(word: &str, players: usize) -> Option<usize> {
+    match word.starts_with("p") {
+        true => word[1..].parse::<usize>().ok().and_then(|number| match number >= 1 && number <= players {
+            true => Some(number - 1),
+            false => None,
+        }),
+        false => None,
+    }
+}
+
+/// PHH writes cards run together in standard notation, like "AhTd".
+fn phh_cards(cards: &Vec<Card>) -> String {
+    cards.iter().map(|card| card.notated(Notation::Standard).to_string()).collect::<Vec<String>>().concat()
+}
+
+fn parse_cards(s: &str) -> Result<Vec<Card>, HistoryParseErr> {
+    let chars = s.chars().collect::<Vec<char>>();
+    if chars.len() % 2 != 0 {
+        return Err(HistoryParseErr::BadCards(s.to_string()));
+    }
+    chars.chunks(2)
+         .map(|pair| pair.iter().map(|&char| char).collect::<String>())
+         .map(|card| card.parse::<Card>().ok().ok_or(HistoryParseErr::BadCards(s.to_string())))
+         .collect()
+}
+
+fn integer_array(values: &Vec<u32>) -> String {
+    format!("[{}]", values.iter().map(|value| value.to_string()).collect::<Vec<String>>().connect(", "))
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum TomlValue {
+    Text(String),
+    Integer(i64),
+    Boolean(bool),
+    Array(Vec<TomlValue>),
+    /// Floats, dates and anything else nothing here reads.
+    Other(String),
+}
+
+fn field<'a>(fields: &'a Vec<(String, TomlValue)>, key: &str) -> Option<&'a TomlValue> {
+    fields.iter().find(|&&(ref name, _)| name.as_slice() == key).map(|&(_, ref value)| value)
+}
+
+fn integer(fields: &Vec<(String, TomlValue)>, key: &str) -> Result<u32, HistoryParseErr> {
+    match field(fields, key) {
+        Some(&TomlValue::Integer(value)) if value >= 0 => Ok(value as u32),
+        Some(_) => Err(HistoryParseErr::BadAmount(key.to_string())),
+        None => Err(HistoryParseErr::MissingField(key.to_string())),
+    }
+}
+
+fn integers(fields: &Vec<(String, TomlValue)>, key: &str) -> Result<Vec<u32>, HistoryParseErr> {
+    match field(fields, key) {
+        Some(&TomlValue::Array(ref values)) => values.iter()
+                                                     .map(|value| match *value {
+                                                         TomlValue::Integer(value) if value >= 0 => Ok(value as u32),
+                                                         _ => Err(HistoryParseErr::BadAmount(key.to_string())),
+                                                     })
+                                                     .collect(),
+        Some(_) => Err(HistoryParseErr::BadAmount(key.to_string())),
+        None => Err(HistoryParseErr::MissingField(key.to_string())),
+    }
+}
+
+fn text_field(fields: &Vec<(String, TomlValue)>, key: &str) -> Result<String, HistoryParseErr> {
+    match field(fields, key) {
+        Some(&TomlValue::Text(ref text)) => Ok(text.clone()),
+        Some(_) => Err(HistoryParseErr::BadLine(key.to_string())),
+        None => Err(HistoryParseErr::MissingField(key.to_string())),
+    }
+}
+
+fn texts(fields: &Vec<(String, TomlValue)>, key: &str) -> Result<Vec<String>, HistoryParseErr> {
+    match field(fields, key) {
+        Some(&TomlValue::Array(ref values)) => values.iter()
+                                                     .map(|value| match *value {
+                                                         TomlValue::Text(ref text) => Ok(text.clone()),
+                                                         _ => Err(HistoryParseErr::BadLine(key.to_string())),
+                                                     })
+                                                     .collect(),
+        Some(_) => Err(HistoryParseErr::BadLine(key.to_string())),
+        None => Err(HistoryParseErr::MissingField(key.to_string())),
+    }
+}
+
+/// Reads the part of TOML that PHH files use: bare keys set to strings, integers, booleans and arrays of those.
+/// Tables like `[_custom]` hold extensions nothing here reads, so everything after one is skipped.
+fn parse_toml(text: &str) -> Result<Vec<(String, TomlValue)>, HistoryParseErr> {
+    let chars = text.chars().collect::<Vec<char>>();
+    let mut position = 0;
+    let mut fields = vec![];
+    let mut in_table = false;
+    loop {
+        skip_blank(&chars, &mut position);
+        if position >= chars.len() {
+            return Ok(fields);
+        }
+        if chars[position] == '[' {
+            in_table = true;
+            skip_line(&chars, &mut position);
+            continue;
+        }
+        let key_start = position;
+        while position < chars.len() && chars[position] != '=' && chars[position] != '\n' {
+            position += 1;
+        }
+        if position >= chars.len() || chars[position] != '=' {
+            return Err(bad_toml(&chars, key_start));
+        }
+        let key = chars[key_start..position].iter().map(|&char| char).collect::<String>().trim().to_string();
+        position += 1;
+        let value = try!(parse_value(&chars, &mut position));
+        skip_spaces(&chars, &mut position);
+        match chars.get(position) {
+            None | Some(&'\n') | Some(&'\r') => (),
+            Some(&'#') => skip_line(&chars, &mut position),
+            Some(_) => return Err(bad_toml(&chars, position)),
+        }
+        if !in_table {
+            fields.push((key, value));
+        }
+    }
+}
+
+fn parse_value(chars: &Vec<char>, position: &mut usize) -> Result<TomlValue, HistoryParseErr> {
+    skip_spaces(chars, position);
+    let start = *position;
+    match chars.get(*position) {
+        Some(&'"') => {
+            *position += 1;
+            let mut text = String::new();
+            loop {
+                match chars.get(*position) {
+                    None | Some(&'\n') => return Err(bad_toml(chars, start)),
+                    Some(&'"') => {
+                        *position += 1;
+                        return Ok(TomlValue::Text(text));
+                    },
+                    Some(&'\\') => {
+                        let escaped = match chars.get(*position + 1) {
+                            Some(&'n') => Some('\n'),
+                            Some(&'t') => Some('\t'),
+                            Some(&'"') => Some('"'),
+                            Some(&'\\') => Some('\\'),
+                            Some(&'u') => {
+                                let digits = chars.iter().skip(*position + 2).take(4).map(|char| char.to_digit(16)).collect::<Vec<Option<u32>>>();
+                                *position += 4;
+                                match digits.len() == 4 && digits.iter().all(|digit| digit.is_some()) {
+                                    true => char::from_u32(digits.iter().fold(0, |code, digit| code * 16 + digit.unwrap())),
+                                    false => None,
+                                }
+                            },
+                            _ => None,
+                        };
+                        match escaped {
+                            Some(escaped) => text.push(escaped),
+                            None => return Err(bad_toml(chars, start)),
+                        }
+                        *position += 2;
+                    },
+                    Some(&char) => {
+                        text.push(char);
+                        *position += 1;
+                    },
+                }
+            }
+        },
+        Some(&'[') => {
+            *position += 1;
+            let mut values = vec![];
+            loop {
+                skip_blank(chars, position);
+                match chars.get(*position) {
+                    Some(&']') => {
+                        *position += 1;
+                        return Ok(TomlValue::Array(values));
+                    },
+                    None => return Err(bad_toml(chars, start)),
+                    _ => (),
+                }
+                values.push(try!(parse_value(chars, position)));
+                skip_blank(chars, position);
+                match chars.get(*position) {
+                    Some(&',') => *position += 1,
+                    Some(&']') => (),
+                    _ => return Err(bad_toml(chars, start)),
+                }
+            }
+        },
+        Some(_) => {
+            while let Some(&char) = chars.get(*position) {
+                if char == ',' || char == ']' || char == '\n' || char == '#' {
+                    break;
+                }
+                *position += 1;
+            }
+            let token = chars[start..*position].iter().map(|&char| char).collect::<String>();
+            let token = token.trim();
+            Ok(match token {
+                "" => return Err(bad_toml(chars, start)),
+                "true" => TomlValue::Boolean(true),
+                "false" => TomlValue::Boolean(false),
+                _ => match token.replace("_", "").as_slice().parse::<i64>() {
+                    Ok(value) => TomlValue::Integer(value),
+                    Err(_) => TomlValue::Other(token.to_string()),
+                },
+            })
+        },
+        None => Err(bad_toml(chars, start)),
+    }
+}
+
+fn skip_spaces(chars: &Vec<char>, position: &mut usize) {
+    while *position < chars.len() && (chars[*position] == ' ' || chars[*position] == '\t') {
+        *position += 1;
+    }
+}
+
+fn skip_line(chars: &Vec<char>, position: &mut usize) {
+    while *position < chars.len() && chars[*position] != '\n' {
+        *position += 1;
+    }
+}
+
+/// Skips whitespace, line breaks and comments.
+fn skip_blank(chars: &Vec<char>, position: &mut usize) {
+    while *position < chars.len() {
+        match chars[*position] {
+            ' ' | '\t' | '\r' | '\n' => *position += 1,
+            '#' => skip_line(chars, position),
+            _ => return,
+        }
+    }
+}
+
+fn bad_toml(chars: &Vec<char>, position: usize) -> HistoryParseErr {
+    HistoryParseErr::BadLine(chars.iter().skip(position).take_while(|&&char| char != '\n').map(|&char| char).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, write, parse_toml, TomlValue};
+    use history::{Post, Blind, ActionRecord, HistoryParseErr};
+    use table::{Action, Street, Limit, played_hand};
+
+    static HAND: &'static str = r#"variant = "NT"
+ante_trimming_status = false
+antes = [0, 0, 0]
+blinds_or_straddles = [1, 2, 0]
+min_bet = 2
+starting_stacks = [150, 200, 200]
+actions = [
+  "d dh p1 TcTd",
+  "d dh p2 ????",
+  "d dh p3 AhKd",
+  "p3 cbr 6",
+  "p1 cc",
+  "p2 f",
+  "d db 2c7dTh",
+  "p1 cc",
+  "p3 cbr 10",
+  "p1 cc",
+  "d db Js",
+  "p1 cbr 20",
+  "p3 cbr 60",
+  "p1 cc",
+  "d db 5h",
+  "p1 cbr 74",
+  "p3 cc",
+  "p1 sm TcTd",
+  "p3 sm AhKd"
+]
+players = ["Bob Smith", "Bob", "Alice"]
+finishing_stacks = [302, 198, 50]"#;
+
+    #[test] fn hands_are_read_in_player_order() {
+        let history = parse(HAND).ok().unwrap();
+        assert_eq!(history.seats[0].name, "Bob Smith");
+        assert_eq!(history.button, 2);
+        assert_eq!(history.posts, vec![Post::new(0, Blind::SmallBlind, 1), Post::new(1, Blind::BigBlind, 2)]);
+        assert!(history.hole_cards[1].is_none());
+    }
+
+    #[test] fn actions_are_read_with_what_they_put_in() {
+        let history = parse(HAND).ok().unwrap();
+        assert_eq!(history.actions[0], ActionRecord::new(Street::Preflop, 2, Action::Raise(6), 6));
+        assert_eq!(history.actions[1], ActionRecord::new(Street::Preflop, 0, Action::Call, 5));
+        assert_eq!(history.actions[3], ActionRecord::new(Street::Flop, 0, Action::Check, 0));
+        assert_eq!(history.actions[6], ActionRecord::new(Street::Turn, 0, Action::Bet(20), 20));
+        assert_eq!(history.total_pot(), 302);
+        assert_eq!(history.board.len(), 5);
+    }
+
+    #[test] fn winnings_come_from_finishing_stacks() {
+        assert_eq!(parse(HAND).ok().unwrap().winnings, vec![302, 0, 0]);
+    }
+
+    #[test] fn winnings_are_worked_out_without_finishing_stacks() {
+        let text = HAND.replace("\nfinishing_stacks = [302, 198, 50]", "");
+        let history = parse(text.as_slice()).ok().unwrap();
+        assert_eq!(history.winnings, vec![302, 0, 0]);
+        assert_eq!(history.showdown[0].description, "Three of a Kind, Tens");
+    }
+
+    #[test] fn reading_and_writing_round_trips() {
+        assert_eq!(write(&parse(HAND).ok().unwrap()).unwrap(), HAND);
+    }
+
+    #[test] fn table_hands_round_trip() {
+        let history = played_hand().history();
+        let written = write(&history).unwrap();
+        let read = parse(written.as_slice()).ok().unwrap();
+        // The button is written last, so the seats come back the other way round.
+        assert_eq!(read.winnings, vec![32, 0]);
+        assert_eq!(read.total_pot(), history.total_pot());
+        assert_eq!(write(&read).unwrap(), written);
+    }
+
+    #[test] fn pot_limit_has_no_phh_variant() {
+        let mut history = parse(HAND).ok().unwrap();
+        history.limit = Limit::PotLimit;
+        assert!(write(&history).is_none());
+    }
+
+    #[test] fn other_variants_are_not_read() {
+        let text = HAND.replace("\"NT\"", "\"PO\"");
+        assert_eq!(parse(text.as_slice()).err(), Some(HistoryParseErr::Unsupported("PO".to_string())));
+    }
+
+    #[test] fn toml_comments_and_tables_are_skipped() {
+        let fields = parse_toml("# a hand\nevent = \"WSOP \\\"Main\\\"\" # where\nseats = [\n  1, # first\n  2,\n]\n[_custom]\nseats = 3\n").ok().unwrap();
+        assert_eq!(fields, vec![("event".to_string(), TomlValue::Text("WSOP \"Main\"".to_string())),
+                                ("seats".to_string(), TomlValue::Array(vec![TomlValue::Integer(1), TomlValue::Integer(2)]))]);
+    }
+}
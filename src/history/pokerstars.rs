@@ -0,0 +1,394 @@
+use card::Card;
+use hand::Hand;
+use table::{Street, Action, Limit};
+use super::{HandHistory, HistoryParseErr, SeatRecord, Post, Blind, ActionRecord, ShowdownRecord};
+
+use std::cmp::min;
+use std::iter::repeat;
+
+/// Reads one Hold'em hand the way PokerStars writes it.
+/// Cash game amounts come out in cents, tournament amounts in chips.
+/// Seats are numbered from 0 in the order they're listed, whatever seat numbers the table used.
+pub fn parse(text: &str) -> Result<HandHistory, HistoryParseErr> {
+    let lines = text.lines()
+                    .map(|line| line.trim())
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<&str>>();
+    let header = match lines.first() {
+        Some(&header) if header.starts_with("PokerStars ") && header.contains("Hold'em") => header,
+        _ => return Err(HistoryParseErr::MissingHeader),
+    };
+    let cash_game = !header.contains("Tournament") &&
+        (header.contains("$") || header.contains("€") || header.contains("£"));
+    let scale = match cash_game {
+        true => 100,
+        false => 1,
+    };
+    let (low_stake, high_stake) = try!(parse_stakes(header, scale));
+    let (limit, small_blind, big_blind) = if header.contains("Pot Limit") {
+        (Limit::PotLimit, low_stake, high_stake)
+    } else if header.contains("No Limit") {
+        (Limit::NoLimit, low_stake, high_stake)
+    } else {
+        // Limit games are named by their bet sizes; the blinds are half of those.
+        (Limit::FixedLimit { small_bet: low_stake, big_bet: high_stake, cap: 4 }, low_stake / 2, low_stake)
+    };
+
+    let body = lines.iter()
+                    .skip(1)
+                    .take_while(|line| !line.starts_with("*** SUMMARY"))
+                    .map(|&line| line)
+                    .collect::<Vec<&str>>();
+
+    let mut seat_numbers = vec![];
+    let mut seats = vec![];
+    for &line in body.iter().filter(|line| is_seat_line(**line)) {
+        let (number, seat) = try!(parse_seat(line, scale));
+        seat_numbers.push(number);
+        seats.push(seat);
+    }
+    let button_number = body.iter()
+                            .filter(|line| line.starts_with("Table "))
+                            .filter_map(|line| line.find("Seat #").map(|start| &line[start + "Seat #".len()..]))
+                            .filter_map(|rest| rest.split(' ').next().and_then(|number| number.parse::<usize>().ok()))
+                            .next();
+    let button = seat_numbers.iter().position(|&number| Some(number) == button_number).unwrap_or(0);
+
+    let players = seats.len();
+    let mut history = HandHistory {
+        seats: seats,
+        button: button,
+        small_blind: small_blind,
+        big_blind: big_blind,
+        ante: 0,
+        limit: limit,
+        hole_cards: repeat(None).take(players).collect(),
+        posts: vec![],
+        actions: vec![],
+        board: vec![],
+        showdown: vec![],
+        winnings: repeat(0).take(players).collect(),
+    };
+    let mut street = Street::Preflop;
+    let mut street_bets = repeat(0).take(players).collect::<Vec<u32>>();
+
+    for &line in body.iter() {
+        if line.starts_with("Table ") || is_seat_line(line) {
+            continue;
+        }
+        if line.starts_with("*** ") {
+            let next = if line.starts_with("*** FLOP") {
+                Street::Flop
+            } else if line.starts_with("*** TURN") {
+                Street::Turn
+            } else if line.starts_with("*** RIVER") {
+                Street::River
+            } else if line.starts_with("*** SHOW DOWN") {
+                Street::Showdown
+            } else {
+                street
+            };
+            if next != street && next != Street::Showdown {
+                history.board.extend(try!(bracketed_cards(line)).into_iter());
+                street_bets = repeat(0).take(players).collect();
+            }
+            street = next;
+            continue;
+        }
+        if line.starts_with("Dealt to ") {
+            let (seat, rest) = try!(player_line(&history.seats, &line["Dealt to ".len()..], " ")
+                                    .ok_or(HistoryParseErr::UnknownPlayer(line.to_string())));
+            history.hole_cards[seat] = Some(Hand::new(try!(bracketed_cards(rest))));
+            continue;
+        }
+        if line.starts_with("Uncalled bet (") {
+            let close = try!(line.find(')').ok_or(HistoryParseErr::BadLine(line.to_string())));
+            let amount = try!(parse_amount(&line["Uncalled bet (".len()..close], scale));
+            let (seat, _) = try!(player_line(&history.seats, &line[close..], ") returned to ")
+                                 .ok_or(HistoryParseErr::UnknownPlayer(line.to_string())));
+            history.winnings[seat] += amount;
+            continue;
+        }
+        match player_line(&history.seats, line, ": ") {
+            Some((seat, rest)) => try!(record_action(&mut history, &mut street_bets, street, seat, rest, scale)),
+            None => match player_line(&history.seats, line, " collected ") {
+                Some((seat, rest)) => history.winnings[seat] += try!(parse_amount(first_word(rest), scale)),
+                // Chat, disconnections, players joining the table and the like.
+                None => (),
+            },
+        }
+    }
+    Ok(history)
+}
+
+/// Reads every hand in a file of PokerStars histories, one result per hand.
+pub fn parse_all(text: &str) -> Vec<Result<HandHistory, HistoryParseErr>> {
+    let mut hands: Vec<Vec<&str>> = vec![];
+    for line in text.trim_left_matches('\u{feff}').lines() {
+        if hands.is_empty() || line.trim().starts_with("PokerStars ") {
+            hands.push(vec![]);
+        }
+        hands.last_mut().unwrap().push(line);
+    }
+    hands.iter()
+         .filter(|lines| lines.iter().any(|line| !line.trim().is_empty()))
+         .map(|lines| parse(lines.connect("\n").as_slice()))
+         .collect()
+}
+
+fn record_action(history: &mut HandHistory, street_bets: &mut Vec<u32>, street: Street, seat: usize, rest: &str,
+                 scale: u32) -> Result<(), HistoryParseErr> {
+    // "and is all-in", "and has reached the cap" and so on don't change what was put in.
+    let rest = match rest.find(" and ") {
+        Some(end) => &rest[..end],
+        None => rest,
+    };
+    if rest == "folds" {
+        history.actions.push(ActionRecord::new(street, seat, Action::Fold, 0));
+    } else if rest == "checks" {
+        history.actions.push(ActionRecord::new(street, seat, Action::Check, 0));
+    } else if rest.starts_with("calls ") {
+        let amount = try!(parse_amount(&rest["calls ".len()..], scale));
+        street_bets[seat] += amount;
+        history.actions.push(ActionRecord::new(street, seat, Action::Call, amount));
+    } else if rest.starts_with("bets ") {
+        let amount = try!(parse_amount(&rest["bets ".len()..], scale));
+        street_bets[seat] += amount;
+        history.actions.push(ActionRecord::new(street, seat, Action::Bet(street_bets[seat]), amount));
+    } else if rest.starts_with("raises ") {
+        let to = match rest.find(" to ") {
+            Some(start) => try!(parse_amount(&rest[start + " to ".len()..], scale)),
+            None => return Err(HistoryParseErr::BadLine(rest.to_string())),
+        };
+        if to < street_bets[seat] {
+            return Err(HistoryParseErr::BadAmount(rest.to_string()));
+        }
+        let amount = to - street_bets[seat];
+        street_bets[seat] = to;
+        history.actions.push(ActionRecord::new(street, seat, Action::Raise(to), amount));
+    } else if rest.starts_with("posts ") {
+        let amount = try!(parse_amount(last_word(rest), scale));
+        let blind = if rest.starts_with("posts the ante ") {
+            history.ante = amount;
+            Blind::Ante
+        } else if rest.starts_with("posts small blind ") {
+            street_bets[seat] += amount;
+            Blind::SmallBlind
+        } else {
+            // A big blind, or a small and big blind posted together to come in, the small part of which is dead.
+            street_bets[seat] += min(amount, history.big_blind);
+            Blind::BigBlind
+        };
+        history.posts.push(Post::new(seat, blind, amount));
+    } else if rest.starts_with("shows ") {
+        let hole_cards = Hand::new(try!(bracketed_cards(rest)));
+        if street == Street::Showdown {
+            let hand = hole_cards.clone() + Hand::new(history.board.clone());
+            history.showdown.push(ShowdownRecord::new(seat, hole_cards.clone(), &hand));
+        }
+        history.hole_cards[seat] = Some(hole_cards);
+    }
+    // Anything else, like mucking or sitting out, doesn't change the hand.
+    Ok(())
+}
+
+fn is_seat_line(line: &str) -> bool {
+    line.starts_with("Seat ") && line.contains(" in chips)")
+}
+
+/// "Seat 3: Bob ($1.50 in chips)", possibly followed by something like "is sitting out".
+fn parse_seat(line: &str, scale: u32) -> Result<(usize, SeatRecord), HistoryParseErr> {
+    let bad_seat = || HistoryParseErr::BadSeat(line.to_string());
+    let colon = try!(line.find(':').ok_or(bad_seat()));
+    let number = try!(line["Seat ".len()..colon].trim().parse::<usize>().ok().ok_or(bad_seat()));
+    let rest = &line[colon + 1..];
+    let chips_end = try!(rest.find(" in chips)").ok_or(bad_seat()));
+    let open = try!(rest[..chips_end].rfind(" (").ok_or(bad_seat()));
+    let stack = try!(parse_amount(&rest[open + " (".len()..chips_end], scale));
+    Ok((number, SeatRecord::new(rest[..open].trim().to_string(), stack)))
+}
+
+/// The blinds, or for limit games the bet sizes, from the header's "($0.01/$0.02 USD)" or "(10/20)".
+fn parse_stakes(header: &str, scale: u32) -> Result<(u32, u32), HistoryParseErr> {
+    let after_game = &header[header.find("Hold'em").unwrap_or(0)..];
+    let stakes = after_game.split('(')
+                           .skip(1)
+                           .filter_map(|group| group.split(')').next())
+                           .find(|group| group.contains("/"));
+    match stakes {
+        Some(stakes) => {
+            let mut amounts = stakes.split('/');
+            let low = try!(parse_amount(amounts.next().unwrap_or(""), scale));
+            let high = try!(parse_amount(first_word(amounts.next().unwrap_or("")), scale));
+            Ok((low, high))
+        },
+        None => Err(HistoryParseErr::MissingHeader),
+    }
+}
+
+/// "$1,234.50" as 123450 in a cash game, "1,500" as 1500 in a tournament.
+fn parse_amount(s: &str, scale: u32) -> Result<u32, HistoryParseErr> {
+    let digits = s.trim().chars()
+                  .filter(|&char| char != '$' && char != '€' && char != '£' && char != ',')
+                  .collect::<String>();
+    let mut parts = digits.as_slice().split('.');
+    let whole = parts.next().and_then(|whole| whole.parse::<u32>().ok());
+    let fraction = match parts.next() {
+        None => Some(0),
+        Some(fraction) if scale == 100 && fraction.len() == 1 => fraction.parse::<u32>().ok().map(|tenths| tenths * 10),
+        Some(fraction) if scale == 100 && fraction.len() == 2 => fraction.parse::<u32>().ok(),
+        Some(_) => None,
+    };
+    match (whole, fraction, parts.next()) {
+        (Some(whole), Some(fraction), None) => Ok(whole * scale + fraction),
+        _ => Err(HistoryParseErr::BadAmount(s.to_string())),
+    }
+}
+
+/// The cards in the last pair of brackets on the line, so "[2c 7d 8h] [Js]" gives just the turn.
+fn bracketed_cards(line: &str) -> Result<Vec<Card>, HistoryParseErr> {
+    let bad_cards = || HistoryParseErr::BadCards(line.to_string());
+    let open = try!(line.rfind('[').ok_or(bad_cards()));
+    let close = try!(line.rfind(']').ok_or(bad_cards()));
+    if close < open {
+        return Err(bad_cards());
+    }
+    line[open + 1..close].split(' ')
+                         .filter(|card| !card.is_empty())
+                         .map(|card| card.parse::<Card>().ok().ok_or(bad_cards()))
+                         .collect()
+}
+
+/// The seat whose name starts the line, followed by `separator`, and the rest of the line.
+/// Names can contain spaces, so the longest name that fits wins.
+fn player_line<'a>(seats: &Vec<SeatRecord>, line: &'a str, separator: &str) -> Option<(usize, &'a str)> {
+    seats.iter()
+         .enumerate()
+         .filter(|&(_, seat)| line.starts_with(format!("{}{}", seat.name, separator).as_slice()))
+         .max_by(|&(_, seat)| seat.name.len())
+         .map(|(index, seat)| (index, &line[seat.name.len() + separator.len()..]))
+}
+
+fn first_word(s: &str) -> &str {
+    s.trim().split(' ').next().unwrap_or("")
+}
+
+fn last_word(s: &str) -> &str {
+    s.trim().split(' ').last().unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, parse_all};
+    use card::{Card, Value, Suit};
+    use history::{Post, Blind, ActionRecord, HistoryParseErr};
+    use table::{Street, Action};
+
+    static CASH_HAND: &'static str = "\
+PokerStars Hand #1000000001:  Hold'em No Limit ($0.01/$0.02 USD) - 2015/01/20 21:05:43 ET
+Table 'Alcyone' 6-max Seat #1 is the button
+Seat 1: Alice ($2.00 in chips)
+Seat 3: Bob Smith ($1.50 in chips)
+Seat 4: Bob ($2 in chips)
+Bob Smith: posts small blind $0.01
+Bob: posts big blind $0.02
+*** HOLE CARDS ***
+Dealt to Alice [Ah Kd]
+Alice: raises $0.04 to $0.06
+Bob Smith: calls $0.05
+Bob: folds
+*** FLOP *** [2c 7d Th]
+Bob Smith: checks
+Alice: bets $0.10
+Bob Smith: calls $0.10
+*** TURN *** [2c 7d Th] [Js]
+Bob Smith: bets $0.20
+Alice: raises $0.40 to $0.60
+Bob Smith: calls $0.40
+*** RIVER *** [2c 7d Th Js] [5h]
+Bob Smith: bets $0.74 and is all-in
+Alice: calls $0.74
+*** SHOW DOWN ***
+Bob Smith: shows [Tc Td] (three of a kind, Tens)
+Alice: shows [Ah Kd] (high card Ace)
+Bob Smith collected $2.95 from pot
+*** SUMMARY ***
+Total pot $3.02 | Rake $0.07
+Board [2c 7d Th Js 5h]
+Seat 1: Alice (button) showed [Ah Kd] and lost with high card Ace
+Seat 3: Bob Smith (small blind) showed [Tc Td] and won ($2.95) with three of a kind, Tens";
+
+    static TOURNAMENT_HAND: &'static str = "\
+PokerStars Hand #2000000002: Tournament #900000001, $1.00+$0.10 USD Hold'em No Limit - Level II (15/30) - 2015/01/20 21:10:00 ET
+Table '900000001 1' 9-max Seat #2 is the button
+Seat 1: Carol (1,470 in chips)
+Seat 2: Dave (1530 in chips)
+Carol: posts the ante 5
+Dave: posts the ante 5
+Dave: posts small blind 15
+Carol: posts big blind 30
+*** HOLE CARDS ***
+Dave: folds
+Uncalled bet (15) returned to Carol
+Carol collected 40 from pot
+Carol: doesn't show hand
+*** SUMMARY ***
+Total pot 40 | Rake 0";
+
+    #[test] fn cash_games_are_read_in_cents() {
+        let history = parse(CASH_HAND).ok().unwrap();
+        assert_eq!(history.seats.iter().map(|seat| seat.stack).collect::<Vec<u32>>(), vec![200, 150, 200]);
+        assert_eq!((history.small_blind, history.big_blind), (1, 2));
+        assert_eq!(history.total_pot(), 302);
+        assert_eq!(history.winnings, vec![0, 295, 0]);
+    }
+
+    #[test] fn seats_are_numbered_in_the_order_listed() {
+        let history = parse(CASH_HAND).ok().unwrap();
+        assert_eq!(history.seats[1].name, "Bob Smith");
+        assert_eq!(history.button, 0);
+        assert_eq!(history.posts, vec![Post::new(1, Blind::SmallBlind, 1), Post::new(2, Blind::BigBlind, 2)]);
+    }
+
+    #[test] fn raises_record_the_total_and_what_was_added() {
+        let history = parse(CASH_HAND).ok().unwrap();
+        assert_eq!(history.actions[0], ActionRecord::new(Street::Preflop, 0, Action::Raise(6), 6));
+        assert_eq!(history.actions[1], ActionRecord::new(Street::Preflop, 1, Action::Call, 5));
+        assert_eq!(history.actions[7], ActionRecord::new(Street::Turn, 0, Action::Raise(60), 60));
+        assert_eq!(history.actions[8], ActionRecord::new(Street::River, 1, Action::Bet(74), 74));
+    }
+
+    #[test] fn board_and_showdown_are_read() {
+        let history = parse(CASH_HAND).ok().unwrap();
+        assert_eq!(history.board.len(), 5);
+        assert_eq!(history.board[2], Card::new(Value::Ten, Suit::Heart));
+        assert!(history.hole_cards[0].is_some() && history.hole_cards[1].is_some() && history.hole_cards[2].is_none());
+        assert_eq!(history.showdown[0].seat, 1);
+        assert_eq!(history.showdown[0].description, "Three of a Kind, Tens");
+    }
+
+    #[test] fn tournaments_are_read_in_chips_with_antes() {
+        let history = parse(TOURNAMENT_HAND).ok().unwrap();
+        assert_eq!(history.seats[0].stack, 1470);
+        assert_eq!((history.small_blind, history.big_blind, history.ante), (15, 30, 5));
+        assert_eq!(history.button, 1);
+        assert_eq!(history.winnings, vec![55, 0]);
+        assert_eq!(history.total_pot(), 55);
+    }
+
+    #[test] fn a_file_holds_many_hands() {
+        let file = format!("{}\n\n\n{}\n", CASH_HAND, TOURNAMENT_HAND);
+        let hands = parse_all(file.as_slice());
+        assert_eq!(hands.len(), 2);
+        assert!(hands.iter().all(|hand| hand.is_ok()));
+    }
+
+    #[test] fn bad_cards_are_an_error() {
+        let text = CASH_HAND.replace("Dealt to Alice [Ah Kd]", "Dealt to Alice [Ah Kx]");
+        assert_eq!(parse(text.as_slice()).err(), Some(HistoryParseErr::BadCards("[Ah Kx]".to_string())));
+    }
+
+    #[test] fn other_sites_are_not_read() {
+        assert_eq!(parse("Full Tilt Poker Game #1: Hold'em").err(), Some(HistoryParseErr::MissingHeader));
+    }
+}
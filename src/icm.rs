@@ -0,0 +1,136 @@
+use std::cmp::{min, Ordering};
+use std::collections::HashMap;
+use std::iter::repeat;
+use std::num::Float;
+use std::rand::{thread_rng, Rng};
+
+/// Beyond this many subsets of players left, working ICM out exactly is slower than sampling it.
+static MAX_EXACT_STATES: u64 = 200000;
+pub static ICM_SAMPLES: usize = 20000;
+
+/// Each player's share of the prize pool under the Independent Chip Model, with `payouts` from first
+/// place down. Worked out exactly where that's cheap enough, and by sampling for big fields.
+pub fn equities(stacks: &Vec<u32>, payouts: &Vec<f32>) -> Vec<f32> {
+    match exact_states(stacks.len(), payouts.len()) <= MAX_EXACT_STATES {
+        true => malmuth_harville(stacks, payouts),
+        false => monte_carlo(stacks, payouts, ICM_SAMPLES),
+    }
+}
+
+/// ICM by Malmuth-Harville: each place goes to one of the players left with a chance in proportion to
+/// their stack. Players with no chips finish below everyone with some, in no particular order.
+/// Players are kept track of in a bitmask, so there can be at most 63 of them; `equities` never asks for more.
+fn malmuth_harville(stacks: &Vec<u32>, payouts: &Vec<f32>) -> Vec<f32> {
+    let mut memo = HashMap::new();
+    remaining_equities((1u64 << stacks.len()) - 1, stacks, payouts, &mut memo)
+}
+
+/// What the players in `left` can expect from the places still to be decided, remembered by who's left.
+fn remaining_equities(left: u64, stacks: &Vec<u32>, payouts: &Vec<f32>, memo: &mut HashMap<u64, Vec<f32>>) -> Vec<f32> {
+    let players = stacks.len();
+    let mut equities = repeat(0.0).take(players).collect::<Vec<f32>>();
+    let in_left = range(0, players).filter(|&player| left & (1u64 << player) != 0).collect::<Vec<usize>>();
+    let place = players - in_left.len();
+    if in_left.is_empty() || place >= payouts.len() {
+        return equities;
+    }
+    match memo.get(&left) {
+        Some(known) => return known.clone(),
+        None => (),
+    }
+    let total = in_left.iter().fold(0.0, |total, &player| total + stacks[player] as f32);
+    for &player in in_left.iter() {
+        let chance = match total > 0.0 {
+            true => stacks[player] as f32 / total,
+            false => 1.0 / in_left.len() as f32,
+        };
+        if chance == 0.0 {
+            continue;
+        }
+        let rest = remaining_equities(left & !(1u64 << player), stacks, payouts, memo);
+        equities[player] += chance * payouts[place];
+        for (equity, share) in equities.iter_mut().zip(rest.iter()) {
+            *equity += chance * *share;
+        }
+    }
+    memo.insert(left, equities.clone());
+    equities
+}
+
+/// ICM by sampling finishing orders. Giving each player an exponentially distributed time to win
+/// with a rate of their stack and sorting finishes them in the same order Malmuth-Harville would.
+pub fn monte_carlo(stacks: &Vec<u32>, payouts: &Vec<f32>, samples: usize) -> Vec<f32> {
+    let mut rng = thread_rng();
+    let players = stacks.len();
+    let mut totals = repeat(0.0).take(players).collect::<Vec<f32>>();
+    for _ in range(0, samples) {
+        let mut finish = range(0, players)
+                         .filter(|&player| stacks[player] > 0)
+                         .map(|player| (-(1.0 - rng.gen::<f32>()).ln() / stacks[player] as f32, player))
+                         .collect::<Vec<(f32, usize)>>();
+        finish.sort_by(|left, right| left.0.partial_cmp(&right.0).unwrap_or(Ordering::Equal));
+        let mut busted = range(0, players).filter(|&player| stacks[player] == 0).collect::<Vec<usize>>();
+        rng.shuffle(busted.as_mut_slice());
+        let order = finish.iter().map(|&(_, player)| player).chain(busted.into_iter());
+        for (place, player) in order.take(payouts.len()).enumerate() {
+            totals[player] += payouts[place];
+        }
+    }
+    totals.iter().map(|total| total / samples as f32).collect()
+}
+
+/// How many sets of players left the exact calculation visits, going no further than the paid places.
+fn exact_states(players: usize, paid: usize) -> u64 {
+    if players >= 64 {
+        return MAX_EXACT_STATES + 1;
+    }
+    let mut states = 0;
+    let mut subsets: u64 = 1;
+    for removed in range(0, min(paid, players) + 1) {
+        states += subsets;
+        if states > MAX_EXACT_STATES {
+            break;
+        }
+        subsets = subsets * (players - removed) as u64 / (removed + 1) as u64;
+    }
+    states
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{equities, malmuth_harville, monte_carlo};
+    use std::num::Float;
+
+    fn close(left: &Vec<f32>, right: &Vec<f32>, tolerance: f32) -> bool {
+        left.len() == right.len() && left.iter().zip(right.iter()).all(|(left, right)| (*left - *right).abs() < tolerance)
+    }
+
+    #[test] fn winner_take_all_is_the_chip_share() {
+        assert!(close(&malmuth_harville(&vec![3000, 1000], &vec![100.0]), &vec![75.0, 25.0], 0.001));
+    }
+
+    #[test] fn three_way_icm_matches_the_worked_example() {
+        let equities = malmuth_harville(&vec![5000, 3000, 2000], &vec![50.0, 30.0, 20.0]);
+        assert!((equities[0] - 38.393).abs() < 0.01);
+        assert!((equities.iter().fold(0.0, |sum, equity| sum + *equity) - 100.0).abs() < 0.001);
+        assert!(equities[0] > equities[1] && equities[1] > equities[2]);
+    }
+
+    #[test] fn busted_players_take_the_places_left() {
+        assert!(close(&malmuth_harville(&vec![100, 0], &vec![10.0, 5.0]), &vec![10.0, 5.0], 0.001));
+    }
+
+    #[test] fn sampling_comes_close_to_the_exact_answer() {
+        let stacks = vec![5000, 3000, 2000, 1000];
+        let payouts = vec![50.0, 30.0, 20.0];
+        assert!(close(&monte_carlo(&stacks, &payouts, 20000), &malmuth_harville(&stacks, &payouts), 0.5));
+    }
+
+    #[test] fn big_fields_are_sampled() {
+        let stacks = range(0, 100).map(|player| 1000 + 10 * player).collect::<Vec<u32>>();
+        let payouts = range(0, 15).map(|place| 100.0 / (place + 1) as f32).collect::<Vec<f32>>();
+        let equities = equities(&stacks, &payouts);
+        let pool = payouts.iter().fold(0.0, |sum, payout| sum + *payout);
+        assert!((equities.iter().fold(0.0, |sum, equity| sum + *equity) - pool).abs() < 0.01 * pool);
+    }
+}
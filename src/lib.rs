@@ -0,0 +1,26 @@
+pub mod card;
+pub mod hand;
+pub mod calculations;
+pub mod low;
+pub mod stud;
+pub mod draw;
+pub mod ofc;
+pub mod pineapple;
+pub mod badugi;
+pub mod wild;
+pub mod table;
+pub mod pot;
+pub mod history;
+pub mod replay;
+pub mod stats;
+pub mod icm;
+pub mod range;
+pub mod pushfold;
+pub mod preflop;
+pub mod cfr;
+pub mod subgame;
+pub mod abstraction;
+pub mod arena;
+pub mod duplicate;
+pub mod protocol;
+pub mod odds;
@@ -0,0 +1,156 @@
+use card::{Card, Value};
+use card::Value::*;
+use hand::Hand;
+use std::cmp::Ordering;
+
+extern crate rust_combinatorics;
+use self::rust_combinatorics::combinatorics::binomial::Chooseable;
+
+/// An ace-to-five low hand. Straights and flushes don't count against it, pairs do.
+/// A better low compares greater, the same way a better `Hand` does.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct LowHand {
+    counts: Vec<usize>,
+    ranks: Vec<usize>,
+}
+
+impl LowHand {
+    /// Jokers don't play; `best_with_bug` plays them as the bug.
+    pub fn new(cards: &Vec<Card>) -> LowHand {
+        LowHand::from_ranks(cards.iter().filter_map(|card| ace_low_rank(card.value())).collect())
+    }
+
+    fn from_ranks(ranks: Vec<usize>) -> LowHand {
+        let mut counted: Vec<(usize, usize)> = vec![];
+        for &rank in ranks.iter() {
+            match counted.iter().position(|&(_, counted_rank)| counted_rank == rank) {
+                Some(index) => counted[index].0 += 1,
+                None => counted.push((1, rank)),
+            }
+        }
+        counted.sort_by(|left, right| right.cmp(left));
+        LowHand {
+            counts: counted.iter().map(|&(count, _)| count).collect(),
+            ranks: counted.iter().map(|&(_, rank)| rank).collect(),
+        }
+    }
+
+    pub fn best(cards: &Vec<Card>) -> Option<LowHand> {
+        match cards.len() > 5 {
+            true => cards.clone().choose(5).map(|cards| LowHand::new(&cards)).max(),
+            false => Some(LowHand::new(cards)),
+        }
+    }
+
+    /// The best low with the hand's jokers played as the bug: the lowest card the hand doesn't already hold.
+    pub fn best_with_bug(hand: &Hand) -> LowHand {
+        let cards = hand.natural_cards();
+        let jokers = hand.jokers();
+        match cards.len() + jokers > 5 && jokers < 5 {
+            true => cards.choose(5 - jokers).map(|cards| LowHand::with_bug(&cards, jokers)).max().unwrap(),
+            false => LowHand::with_bug(&cards, jokers),
+        }
+    }
+
+    fn with_bug(cards: &Vec<Card>, jokers: usize) -> LowHand {
+        let mut ranks = cards.iter().filter_map(|card| ace_low_rank(card.value())).collect::<Vec<usize>>();
+        for _ in range(0, jokers) {
+            let lowest_missing = range(1, 14).find(|rank| !ranks.contains(rank)).unwrap_or(1);
+            ranks.push(lowest_missing);
+        }
+        LowHand::from_ranks(ranks)
+    }
+
+    pub fn best_qualifying(cards: &Vec<Card>, qualifier: Value) -> Option<LowHand> {
+        match cards.len() >= 5 {
+            true => cards.clone().choose(5)
+                         .map(|cards| LowHand::new(&cards))
+                         .filter(|low| low.qualifies(qualifier))
+                         .max(),
+            false => None,
+        }
+    }
+
+    pub fn qualifies(&self, qualifier: Value) -> bool {
+        self.ranks.len() == 5 &&
+            self.counts.iter().all(|&count| count == 1) &&
+            ace_low_rank(&qualifier).map_or(false, |qualifier| self.ranks[0] <= qualifier)
+    }
+}
+
+/// Aces are 1 and kings 13. A joker has no rank of its own.
+pub fn ace_low_rank(value: &Value) -> Option<usize> {
+    match *value {
+        Ace => Some(1),
+        Joker => None,
+        other => Some(other as usize + 2),
+    }
+}
+
+impl PartialOrd for LowHand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LowHand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match other.counts.cmp(&self.counts) {
+            Ordering::Equal => other.ranks.cmp(&self.ranks),
+            order => order,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LowHand, ace_low_rank};
+    use card::Value;
+    use hand::Hand;
+
+    fn parse_low(s: &str) -> LowHand {
+        LowHand::best(&s.parse::<Hand>().ok().unwrap().cards()).unwrap()
+    }
+
+    #[test] fn the_wheel_is_the_best_low() {
+        assert!(parse_low("AS 2S 3S 4S 5S") > parse_low("AH 2D 3C 4S 6H"));
+    }
+
+    #[test] fn lows_compare_from_the_highest_card() {
+        assert!(parse_low("8S 5H 4D 3C 2S") > parse_low("8H 6D 3S 2C AD"));
+    }
+
+    #[test] fn pairs_count_against_a_low() {
+        assert!(parse_low("KS QH JD 9C 8S") > parse_low("AS AH 2D 3C 4S"));
+    }
+
+    #[test] fn best_low_picks_five_of_seven_cards() {
+        assert!(parse_low("KS KH AD 2C 3S 4D 5H") == parse_low("AD 2C 3S 4D 5H"));
+    }
+
+    #[test] fn the_bug_is_the_lowest_missing_card() {
+        let hand = "2S 3H 4D 6C XX".parse::<Hand>().ok().unwrap();
+        assert!(LowHand::best_with_bug(&hand) == parse_low("AS 2S 3H 4D 6C"));
+    }
+
+    #[test] fn the_bug_avoids_pairing() {
+        let hand = "AS 2H 3D 4C XX".parse::<Hand>().ok().unwrap();
+        assert!(LowHand::best_with_bug(&hand) == parse_low("AS 2H 3D 4C 5S"));
+    }
+
+    #[test] fn eight_or_better_qualifies() {
+        let cards = "8S 6H 4D 3C 2S KH KD".parse::<Hand>().ok().unwrap().cards();
+        assert!(LowHand::best_qualifying(&cards, Value::Eight).is_some());
+    }
+
+    #[test] fn nine_low_does_not_qualify_for_eight_or_better() {
+        let cards = "9S 6H 4D 3C 2S KH KD".parse::<Hand>().ok().unwrap().cards();
+        assert!(LowHand::best_qualifying(&cards, Value::Eight).is_none());
+    }
+
+    #[test] fn jokers_have_no_low_rank() {
+        assert_eq!(ace_low_rank(&Value::Ace), Some(1));
+        assert_eq!(ace_low_rank(&Value::King), Some(13));
+        assert_eq!(ace_low_rank(&Value::Joker), None);
+    }
+}
@@ -1,6 +1,7 @@
-mod card;
-mod hand;
-mod calculations;
+extern crate poker;
+
+#[cfg(not(test))]
+use poker::calculations;
 
 #[cfg(not(test))]
 fn main() {
@@ -19,7 +20,7 @@ fn main() {
 #[cfg(not(test))]
 mod support {
     use std::old_io;
-    use hand::Hand;
+    use poker::hand::Hand;
 
     pub fn get_hand(prompt: &str) -> Hand {
 		print!("{}", prompt);
@@ -0,0 +1,75 @@
+use std::num::Float;
+
+// Throughout, `pot` is everything in the middle already, including any bet the player is facing,
+// and equity is the player's chance of winning, as from `calculations::chance_of_winning` or a range's equity.
+// Expected values are in chips, counted from where the player stands now, so folding is worth nothing.
+
+/// How many times `to_call` the pot is offering, as in "3 to 1".
+pub fn pot_odds(pot: u32, to_call: u32) -> f32 {
+    pot as f32 / to_call as f32
+}
+
+/// The least equity that makes calling `to_call` break even.
+pub fn required_equity(pot: u32, to_call: u32) -> f32 {
+    to_call as f32 / (pot + to_call) as f32
+}
+
+/// What calling `to_call` is worth with `equity`, if nothing more goes in after the call.
+pub fn call_ev(pot: u32, to_call: u32, equity: f32) -> f32 {
+    equity * (pot + to_call) as f32 - to_call as f32
+}
+
+/// How much more the player has to win later, on top of the pot, the times they make their hand
+/// for calling to break even, where `equity` is how often they make it. Nothing when the call
+/// already pays on its own, and infinite when they can't make it at all.
+pub fn implied_odds(pot: u32, to_call: u32, equity: f32) -> f32 {
+    if equity <= 0.0 {
+        return Float::infinity();
+    }
+    ((1.0 - equity) * to_call as f32 / equity - pot as f32).max(0.0)
+}
+
+/// What betting `bet` is worth when the opponent folds `fold_frequency` of the time and otherwise
+/// calls, leaving the player with `equity` against the hands that call.
+pub fn bet_ev(pot: u32, bet: u32, fold_frequency: f32, equity: f32) -> f32 {
+    let called = equity * (pot + 2 * bet) as f32 - bet as f32;
+    fold_frequency * pot as f32 + (1.0 - fold_frequency) * called
+}
+
+/// How often the opponent has to fold for a bet of `bet` with no chance of winning when called to break even.
+pub fn break_even_fold_frequency(pot: u32, bet: u32) -> f32 {
+    bet as f32 / (pot + bet) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pot_odds, required_equity, call_ev, implied_odds, bet_ev, break_even_fold_frequency};
+    use std::num::Float;
+
+    #[test] fn a_pot_sized_bet_needs_a_third_to_call() {
+        // Calling 100 into 200 (a 100 pot and the bet).
+        assert_eq!(pot_odds(200, 100), 2.0);
+        assert!((required_equity(200, 100) - 1.0 / 3.0).abs() < 0.0001);
+        assert!(call_ev(200, 100, 1.0 / 3.0).abs() < 0.0001);
+    }
+
+    #[test] fn calling_is_worth_the_share_of_the_final_pot_less_the_call() {
+        assert!((call_ev(200, 100, 0.5) - 50.0).abs() < 0.0001);
+        assert!((call_ev(200, 100, 0.0) + 100.0).abs() < 0.0001);
+    }
+
+    #[test] fn draws_need_to_win_more_later() {
+        // A flush draw with one card to come hits about a fifth of the time.
+        assert!((implied_odds(100, 50, 0.2) - 100.0).abs() < 0.001);
+        assert_eq!(implied_odds(200, 10, 0.5), 0.0);
+        assert!(implied_odds(100, 50, 0.0).is_infinite());
+    }
+
+    #[test] fn bluffs_break_even_when_they_work_often_enough() {
+        let fold_frequency = break_even_fold_frequency(100, 100);
+        assert_eq!(fold_frequency, 0.5);
+        assert!(bet_ev(100, 100, fold_frequency, 0.0).abs() < 0.0001);
+        assert!((bet_ev(100, 100, 1.0, 0.0) - 100.0).abs() < 0.0001);
+        assert!((bet_ev(100, 100, 0.0, 1.0) - 200.0).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,197 @@
+use hand::{Hand, HandRank};
+use card::Value;
+
+/// An Open-Face Chinese hand: three cards on top, five in the middle and five on the bottom.
+#[derive(Debug, Clone)]
+pub struct OfcHand {
+    top: Hand,
+    middle: Hand,
+    bottom: Hand,
+}
+
+#[derive(Debug)]
+pub enum OfcHandErr {
+    WrongRowSize,
+}
+
+impl OfcHand {
+    pub fn new(top: Hand, middle: Hand, bottom: Hand) -> Result<OfcHand, OfcHandErr> {
+        match (top.cards().len(), middle.cards().len(), bottom.cards().len()) {
+            (3, 5, 5) => Ok(OfcHand { top: top, middle: middle, bottom: bottom }),
+            _ => Err(OfcHandErr::WrongRowSize),
+        }
+    }
+
+    pub fn top(&self) -> &Hand {
+        &self.top
+    }
+
+    pub fn middle(&self) -> &Hand {
+        &self.middle
+    }
+
+    pub fn bottom(&self) -> &Hand {
+        &self.bottom
+    }
+
+    /// A hand is fouled when a row beats the row below it.
+    pub fn is_fouled(&self) -> bool {
+        self.top > self.middle || self.middle > self.bottom
+    }
+
+    /// The royalty bonus for all three rows. Fouled hands don't get any.
+    pub fn royalties(&self) -> i32 {
+        match self.is_fouled() {
+            true => 0,
+            false => top_royalty(&self.top) + middle_royalty(&self.middle) + bottom_royalty(&self.bottom),
+        }
+    }
+
+    /// Queens or better on top, without fouling.
+    pub fn qualifies_for_fantasyland(&self) -> bool {
+        !self.is_fouled() && match self.top.rank() {
+            HandRank::ThreeOfAKind => true,
+            HandRank::Pair => self.top.ranked_values()[0] >= Value::Queen,
+            _ => false,
+        }
+    }
+}
+
+fn is_royal_flush(hand: &Hand) -> bool {
+    hand.rank() == HandRank::StraightFlush && hand.ranked_values()[0] == Value::Ace
+}
+
+fn top_royalty(hand: &Hand) -> i32 {
+    let value = hand.ranked_values()[0] as i32;
+    match hand.rank() {
+        HandRank::ThreeOfAKind => 10 + value,
+        HandRank::Pair if value >= Value::Six as i32 => value - 3,
+        _ => 0,
+    }
+}
+
+fn middle_royalty(hand: &Hand) -> i32 {
+    match hand.rank() {
+        HandRank::ThreeOfAKind => 2,
+        HandRank::Straight => 4,
+        HandRank::Flush => 8,
+        HandRank::FullHouse => 12,
+        HandRank::FourOfAKind => 20,
+        HandRank::StraightFlush if is_royal_flush(hand) => 50,
+        HandRank::StraightFlush => 30,
+        _ => 0,
+    }
+}
+
+fn bottom_royalty(hand: &Hand) -> i32 {
+    match hand.rank() {
+        HandRank::Straight => 2,
+        HandRank::Flush => 4,
+        HandRank::FullHouse => 6,
+        HandRank::FourOfAKind => 10,
+        HandRank::StraightFlush if is_royal_flush(hand) => 25,
+        HandRank::StraightFlush => 15,
+        _ => 0,
+    }
+}
+
+static SCOOP_BONUS: i32 = 3;
+
+/// The points `player` wins from `opponent`: one per row won, three more for winning all three,
+/// plus the difference in royalties. A fouled hand loses every row.
+pub fn score(player: &OfcHand, opponent: &OfcHand) -> i32 {
+    match (player.is_fouled(), opponent.is_fouled()) {
+        (true, true) => 0,
+        (true, false) => -(3 + SCOOP_BONUS + opponent.royalties()),
+        (false, true) => 3 + SCOOP_BONUS + player.royalties(),
+        (false, false) => {
+            let rows = row_score(&player.top, &opponent.top) +
+                       row_score(&player.middle, &opponent.middle) +
+                       row_score(&player.bottom, &opponent.bottom);
+            let scoop = match rows {
+                3 => SCOOP_BONUS,
+                -3 => -SCOOP_BONUS,
+                _ => 0,
+            };
+            rows + scoop + player.royalties() - opponent.royalties()
+        },
+    }
+}
+
+fn row_score(row: &Hand, other_row: &Hand) -> i32 {
+    match (row > other_row, row < other_row) {
+        (true, _) => 1,
+        (_, true) => -1,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OfcHand, score};
+    use hand::Hand;
+
+    fn parse_hand(s: &str) -> Hand {
+        s.parse().ok().unwrap()
+    }
+
+    fn ofc_hand(top: &str, middle: &str, bottom: &str) -> OfcHand {
+        OfcHand::new(parse_hand(top), parse_hand(middle), parse_hand(bottom)).ok().unwrap()
+    }
+
+    #[test] fn rows_must_be_the_right_size() {
+        assert!(OfcHand::new(parse_hand("AS KS"), parse_hand("2S 3S 4S 5S 6S"), parse_hand("2H 3H 4H 5H 6H")).is_err());
+    }
+
+    #[test] fn increasing_rows_are_not_fouled() {
+        assert!(!ofc_hand("2S 3D 5C", "6S 6D 8C 9H JD", "AS AH AD KS KD").is_fouled());
+    }
+
+    #[test] fn a_stronger_top_row_fouls() {
+        assert!(ofc_hand("QS QD 5C", "JS JD 8C 9H 2D", "AS AH AD KS KD").is_fouled());
+    }
+
+    #[test] fn top_pair_beats_middle_pair_on_kickers() {
+        assert!(ofc_hand("QS QD 5C", "QH QC 4C 3H 2D", "AS AH AD KS KD").is_fouled());
+    }
+
+    #[test] fn royalties_add_up_across_rows() {
+        assert_eq!(ofc_hand("6S 6D 2C", "7S 7D 7C 9H JD", "2H 5H 8H JH KH").royalties(), 1 + 2 + 4);
+    }
+
+    #[test] fn top_trips_score_big_royalties() {
+        assert_eq!(ofc_hand("2S 2D 2C", "7S 7D 7C 9H 9D", "AH AS AD AC KH").royalties(), 10 + 12 + 10);
+    }
+
+    #[test] fn royal_flush_on_the_bottom() {
+        assert_eq!(ofc_hand("2S 3D 5C", "6S 6D 8C 9H JD", "0H JH QH KH AH").royalties(), 25);
+    }
+
+    #[test] fn fouled_hands_get_no_royalties() {
+        assert_eq!(ofc_hand("AS AD AC", "6S 6D 8C 9H JD", "0H JH QH KH AH").royalties(), 0);
+    }
+
+    #[test] fn queens_on_top_qualify_for_fantasyland() {
+        assert!(ofc_hand("QS QD 2C", "KS KD 8C 9H JD", "AS AH AD KH KC").qualifies_for_fantasyland());
+        assert!(!ofc_hand("JS JD 2C", "KS KD 8C 9H JD", "AS AH AD KH KC").qualifies_for_fantasyland());
+    }
+
+    #[test] fn scooping_wins_six_points() {
+        let winner = ofc_hand("KS 3D 2C", "6S 6D 8C 9H JD", "AS AH AD KD 2D");
+        let loser = ofc_hand("QS 2H 3C", "5S 5D 8D 9S JH", "KH KC KS QD 3D");
+        assert_eq!(score(&winner, &loser), 6);
+        assert_eq!(score(&loser, &winner), -6);
+    }
+
+    #[test] fn split_rows_net_out() {
+        let player = ofc_hand("4S 3D 2C", "6S 6D 8C 9H JD", "AS AH KD KS 2D");
+        let opponent = ofc_hand("5S 2D 0C", "5S 5D 8D 9S JH", "3H 3C 3D QS 4C");
+        assert_eq!(score(&player, &opponent), -1 + 1 - 1);
+    }
+
+    #[test] fn fouling_loses_to_any_live_hand() {
+        let fouled = ofc_hand("AS AD AC", "6S 6D 8C 9H JD", "0H JH QH KH 2H");
+        let live = ofc_hand("2S 3D 5C", "6S 6D 8C 9H JD", "AS AH KD KS 4D");
+        assert_eq!(score(&fouled, &live), -6);
+    }
+}
@@ -0,0 +1,125 @@
+use card::Card;
+use hand::Hand;
+use calculations::chance_of_winning_against;
+
+extern crate rust_combinatorics;
+use self::rust_combinatorics::combinatorics::binomial::Chooseable;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Variant {
+    /// Discard one of three hole cards before the flop.
+    Pineapple,
+    /// Keep all three hole cards until the flop is out, then discard one.
+    CrazyPineapple,
+}
+
+impl Variant {
+    pub fn community_cards_at_discard(&self) -> usize {
+        match *self {
+            Variant::Pineapple => 0,
+            Variant::CrazyPineapple => 3,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PineappleErr {
+    WrongPocketSize,
+    WrongStreet,
+}
+
+/// The chance of winning after throwing away each hole card, against `other_players`.
+/// The discarded card is dead. Opponents are dealt three cards and throw one away at the same point
+/// in the hand, keeping the two that play best with the board as it was then.
+pub fn discard_equities(variant: Variant, my_pocket: &Hand, community_cards: &Hand, other_players: usize) -> Result<Vec<(Card, (f32, f32))>, PineappleErr> {
+    if my_pocket.cards().len() != 3 {
+        return Err(PineappleErr::WrongPocketSize);
+    }
+    if community_cards.cards().len() != variant.community_cards_at_discard() {
+        return Err(PineappleErr::WrongStreet);
+    }
+    Ok(my_pocket.cards().into_iter()
+        .map(|discard| {
+            let discarded = Hand::new(vec![discard]);
+            let kept = my_pocket.clone() - discarded.clone();
+            (discard, chance_of_winning_against(kept, community_cards.clone(), discarded, other_players, 3, opponent_keeps))
+        })
+        .collect())
+}
+
+/// The two of `dealt` that make the best hand with the board as it stood at the discard.
+fn opponent_keeps(dealt: &Hand, board_at_discard: &Hand) -> Hand {
+    dealt.cards().choose(2)
+        .map(|cards| Hand::new(cards))
+        .max_by(|pocket| pocket.clone() + board_at_discard.clone())
+        .unwrap()
+}
+
+/// The hole card whose discard leaves the best chance of winning.
+pub fn best_discard(variant: Variant, my_pocket: &Hand, community_cards: &Hand, other_players: usize) -> Result<Card, PineappleErr> {
+    discard_equities(variant, my_pocket, community_cards, other_players).map(|equities| {
+        equities.into_iter()
+                .fold(None, |best: Option<(Card, f32)>, (card, (chance, _))| {
+                    match best {
+                        Some((_, best_chance)) if best_chance >= chance => best,
+                        _ => Some((card, chance)),
+                    }
+                })
+                .map(|(card, _)| card)
+                .unwrap()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Variant, PineappleErr, discard_equities, best_discard, opponent_keeps};
+    use card::Card;
+    use hand::Hand;
+
+    fn parse_hand(s: &str) -> Hand {
+        s.parse().ok().unwrap()
+    }
+
+    #[test] fn pineapple_needs_three_hole_cards() {
+        match discard_equities(Variant::Pineapple, &parse_hand("AS AH"), &parse_hand(""), 1) {
+            Err(PineappleErr::WrongPocketSize) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test] fn pineapple_discards_before_the_flop() {
+        match discard_equities(Variant::Pineapple, &parse_hand("AS AH 2D"), &parse_hand("KS 7D 3C"), 1) {
+            Err(PineappleErr::WrongStreet) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test] fn crazy_pineapple_discards_on_the_flop() {
+        match discard_equities(Variant::CrazyPineapple, &parse_hand("AS AH 2D"), &parse_hand(""), 1) {
+            Err(PineappleErr::WrongStreet) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test] fn keeping_aces_beats_keeping_ace_seven() {
+        let equities = discard_equities(Variant::Pineapple, &parse_hand("AS AH 7D"), &parse_hand(""), 2).ok().unwrap();
+        let (_, (keep_ace_seven, _)) = equities[0];
+        let (_, (keep_aces, _)) = equities[2];
+        assert!(keep_aces > keep_ace_seven + 0.1);
+        assert_eq!(best_discard(Variant::Pineapple, &parse_hand("7D AS AH"), &parse_hand(""), 2).ok().unwrap(),
+                   "7D".parse().ok().unwrap());
+    }
+
+    #[test] fn crazy_pineapple_keeps_the_set() {
+        let discard = best_discard(Variant::CrazyPineapple, &parse_hand("9S 9H KD"), &parse_hand("9C 5D 2H"), 1).ok().unwrap();
+        assert_eq!(discard, "KD".parse().ok().unwrap());
+    }
+
+    #[test] fn opponents_discard_without_seeing_the_rest_of_the_board() {
+        // Before the flop the kings are kept; on a flop with two deuces the deuce makes trips instead.
+        assert!(opponent_keeps(&parse_hand("KS KH 2D"), &parse_hand("")).cards() == parse_hand("KS KH").cards());
+        let kept = opponent_keeps(&parse_hand("KS KH 2D"), &parse_hand("2C 2S 9H")).cards();
+        assert_eq!(kept.len(), 2);
+        assert!(kept.contains(&"2D".parse::<Card>().ok().unwrap()));
+    }
+}
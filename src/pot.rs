@@ -0,0 +1,140 @@
+use hand::Hand;
+
+use std::cmp::min;
+use std::iter::repeat;
+
+/// Chips in the middle and the seats that can win them.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Pot {
+    amount: u32,
+    eligible: Vec<usize>,
+}
+
+impl Pot {
+    pub fn new(amount: u32, eligible: Vec<usize>) -> Pot {
+        Pot { amount: amount, eligible: eligible }
+    }
+
+    pub fn amount(&self) -> u32 {
+        self.amount
+    }
+
+    pub fn eligible(&self) -> &Vec<usize> {
+        &self.eligible
+    }
+}
+
+/// Splits what each seat put in into a main pot and side pots, one for each all-in amount.
+/// Folded seats' chips go in the pots but they can't win any of them.
+/// A last pot with a single seat eligible is a bet nobody called, going back to whoever made it.
+pub fn build_pots(contributions: &Vec<u32>, folded: &Vec<bool>) -> Vec<Pot> {
+    let live = range(0, contributions.len())
+               .filter(|&seat| !folded[seat] && contributions[seat] > 0)
+               .collect::<Vec<usize>>();
+    let mut levels = live.iter().map(|&seat| contributions[seat]).collect::<Vec<u32>>();
+    levels.sort();
+    levels.dedup();
+
+    let most = contributions.iter().fold(0, |most, &contribution| if contribution > most { contribution } else { most });
+    let mut pots = vec![];
+    let mut previous = 0;
+    let level_count = levels.len();
+    for (index, &level) in levels.iter().enumerate() {
+        // Anything folded seats put in over the biggest live bet goes in the last pot.
+        let top = match index + 1 == level_count {
+            true => most,
+            false => level,
+        };
+        let amount = contributions.iter().fold(0, |amount, &contribution| {
+            amount + min(contribution, top) - min(contribution, previous)
+        });
+        let eligible = live.iter().map(|&seat| seat).filter(|&seat| contributions[seat] >= level).collect();
+        pots.push(Pot::new(amount, eligible));
+        previous = top;
+    }
+    pots
+}
+
+/// What each seat wins. Each pot goes to its best eligible hand, with ties split evenly and odd chips
+/// going one at a time to the tied seats in order, starting from `first_seat`.
+/// Seats without a hand (`None`) can only win a pot nobody else is eligible for.
+pub fn payouts(pots: &Vec<Pot>, hands: &Vec<Option<Hand>>, first_seat: usize) -> Vec<u32> {
+    let seats = hands.len();
+    let mut winnings = repeat(0).take(seats).collect::<Vec<u32>>();
+    for pot in pots.iter() {
+        let shown = pot.eligible.iter()
+                       .filter_map(|&seat| hands[seat].as_ref().map(|hand| (seat, hand)))
+                       .collect::<Vec<(usize, &Hand)>>();
+        let winners = match shown.iter().map(|&(_, hand)| hand).max() {
+            Some(best) => shown.iter().filter(|&&(_, hand)| hand == best).map(|&(seat, _)| seat).collect(),
+            None => pot.eligible.clone(),
+        };
+        let in_order = range(0, seats)
+                       .map(|offset| (first_seat + offset) % seats)
+                       .filter(|seat| winners.contains(seat))
+                       .collect::<Vec<usize>>();
+        if in_order.is_empty() {
+            continue;
+        }
+        let share = pot.amount / in_order.len() as u32;
+        let odd_chips = pot.amount % in_order.len() as u32;
+        for (position, &seat) in in_order.iter().enumerate() {
+            winnings[seat] += share + match (position as u32) < odd_chips {
+                true => 1,
+                false => 0,
+            };
+        }
+    }
+    winnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pot, build_pots, payouts};
+    use hand::Hand;
+
+    fn parse_hand(s: &str) -> Option<Hand> {
+        s.parse().ok()
+    }
+
+    #[test] fn equal_bets_make_one_pot() {
+        assert_eq!(build_pots(&vec![50, 50, 50], &vec![false, false, false]), vec![Pot::new(150, vec![0, 1, 2])]);
+    }
+
+    #[test] fn all_in_players_only_win_what_they_matched() {
+        assert_eq!(build_pots(&vec![20, 50, 100, 100], &vec![false, false, false, false]),
+                   vec![Pot::new(80, vec![0, 1, 2, 3]), Pot::new(90, vec![1, 2, 3]), Pot::new(100, vec![2, 3])]);
+    }
+
+    #[test] fn folded_chips_stay_in_the_pots() {
+        assert_eq!(build_pots(&vec![30, 50, 100], &vec![true, false, false]),
+                   vec![Pot::new(130, vec![1, 2]), Pot::new(50, vec![2])]);
+    }
+
+    #[test] fn uncalled_bets_are_their_own_pot() {
+        assert_eq!(build_pots(&vec![100, 40], &vec![false, false]),
+                   vec![Pot::new(80, vec![0, 1]), Pot::new(60, vec![0])]);
+    }
+
+    #[test] fn folded_chips_over_the_biggest_live_bet_go_in_the_last_pot() {
+        assert_eq!(build_pots(&vec![100, 40, 40], &vec![true, false, false]),
+                   vec![Pot::new(180, vec![1, 2])]);
+    }
+
+    #[test] fn short_stack_wins_only_the_main_pot() {
+        let pots = build_pots(&vec![20, 100, 100], &vec![false, false, false]);
+        let hands = vec![parse_hand("AS AH KS QS 2D 7C 9H"), parse_hand("JD JH KS QS 2D 7C 9H"), parse_hand("3C 4C KS QS 2D 7C 9H")];
+        assert_eq!(payouts(&pots, &hands, 1), vec![60, 160, 0]);
+    }
+
+    #[test] fn ties_split_with_odd_chips_in_seat_order() {
+        let pots = vec![Pot::new(101, vec![0, 1, 2])];
+        let hands = vec![parse_hand("2C 3C AS KS QS JS 0S"), parse_hand("4H 5H AS KS QS JS 0S"), parse_hand("6D 7D AS KS QS JS 0S")];
+        assert_eq!(payouts(&pots, &hands, 1), vec![33, 34, 34]);
+    }
+
+    #[test] fn a_lone_eligible_seat_wins_without_a_showdown() {
+        let pots = vec![Pot::new(30, vec![2])];
+        assert_eq!(payouts(&pots, &vec![None, None, None], 0), vec![0, 0, 30]);
+    }
+}
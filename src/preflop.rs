@@ -0,0 +1,315 @@
+use card::{Card, Suit, Value};
+use calculations::whole_deck;
+use hand::Hand;
+use pushfold::PreflopEquity;
+use range::HandClass;
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::num::Float;
+use std::old_io::{File, IoResult, MemReader};
+
+static MAGIC: &'static [u8] = b"PFEQ";
+pub static TABLE_VERSION: u8 = 1;
+
+/// Each class's equity is stored once for every class from its own onwards, since the other way
+/// round is one minus it: 169 * 170 / 2 entries.
+static STORED_ENTRIES: usize = 14365;
+
+/// The exact table, as written by `generate` and `save`.
+static EXACT_TABLE: &'static [u8] = include_bytes!("preflop_equity.bin");
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum PreflopTableErr {
+    Unreadable,
+    NotATable,
+    UnsupportedVersion(u8),
+}
+
+/// Every hand class's all-in preflop equity against every other, for looking up rather than working out.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PreflopMatrix {
+    equities: Vec<f32>,
+}
+
+impl PreflopMatrix {
+    /// The exact matrix that ships with the crate, for looking equities up without working them out.
+    pub fn exact() -> PreflopMatrix {
+        PreflopMatrix::read(&mut MemReader::new(EXACT_TABLE.to_vec())).ok().unwrap()
+    }
+
+    /// The exact matrix, from every board for every pair of combos. That's around 1.7 million boards
+    /// for each of some 50,000 matchups, which takes this crate's hand evaluator weeks, so use `exact`
+    /// instead; this is for checking the shipped table.
+    pub fn generate() -> PreflopMatrix {
+        PreflopMatrix::tabulate(&ExactEquity)
+    }
+
+    /// Fills the matrix in from any other source of equities.
+    pub fn tabulate<E: PreflopEquity>(source: &E) -> PreflopMatrix {
+        let mut equities = vec![];
+        for hero in HandClass::all().into_iter() {
+            for villain in HandClass::all().into_iter() {
+                let equity = match hero.index() <= villain.index() {
+                    true => source.equity(hero, villain),
+                    false => 1.0 - equities[villain.index() * 169 + hero.index()],
+                };
+                equities.push(equity);
+            }
+        }
+        PreflopMatrix { equities: equities }
+    }
+
+    pub fn lookup(&self, hero: HandClass, villain: HandClass) -> f32 {
+        self.equities[hero.index() * 169 + villain.index()]
+    }
+
+    /// The table as bytes: the magic "PFEQ", a version byte, then each stored equity as a big-endian
+    /// fraction of 65535.
+    pub fn write<W: Writer>(&self, writer: &mut W) -> IoResult<()> {
+        try!(writer.write_all(MAGIC));
+        try!(writer.write_u8(TABLE_VERSION));
+        for hero in range(0, 169) {
+            for villain in range(hero, 169) {
+                try!(writer.write_be_u16((self.equities[hero * 169 + villain] * 65535.0).round() as u16));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn read<R: Reader>(reader: &mut R) -> Result<PreflopMatrix, PreflopTableErr> {
+        match reader.read_exact(MAGIC.len()) {
+            Ok(ref magic) if magic.as_slice() == MAGIC => (),
+            Ok(_) => return Err(PreflopTableErr::NotATable),
+            Err(_) => return Err(PreflopTableErr::Unreadable),
+        }
+        match reader.read_u8() {
+            Ok(version) if version == TABLE_VERSION => (),
+            Ok(version) => return Err(PreflopTableErr::UnsupportedVersion(version)),
+            Err(_) => return Err(PreflopTableErr::Unreadable),
+        }
+        let mut stored = Vec::with_capacity(STORED_ENTRIES);
+        for _ in range(0, STORED_ENTRIES) {
+            match reader.read_be_u16() {
+                Ok(entry) => stored.push(entry as f32 / 65535.0),
+                Err(_) => return Err(PreflopTableErr::Unreadable),
+            }
+        }
+        let mut equities = vec![];
+        for hero in range(0, 169) {
+            for villain in range(0, 169) {
+                equities.push(match hero <= villain {
+                    true => stored[stored_index(hero, villain)],
+                    false => 1.0 - stored[stored_index(villain, hero)],
+                });
+            }
+        }
+        Ok(PreflopMatrix { equities: equities })
+    }
+
+    pub fn save(&self, path: &Path) -> IoResult<()> {
+        let mut file = try!(File::create(path));
+        self.write(&mut file)
+    }
+
+    pub fn load(path: &Path) -> Result<PreflopMatrix, PreflopTableErr> {
+        match File::open(path).and_then(|mut file| file.read_to_end()) {
+            Ok(bytes) => PreflopMatrix::read(&mut MemReader::new(bytes)),
+            Err(_) => Err(PreflopTableErr::Unreadable),
+        }
+    }
+}
+
+impl PreflopEquity for PreflopMatrix {
+    fn equity(&self, hero: HandClass, villain: HandClass) -> f32 {
+        self.lookup(hero, villain)
+    }
+}
+
+/// Where the entry for `hero` against `villain`, with `hero <= villain`, is kept in the stored triangle.
+fn stored_index(hero: usize, villain: usize) -> usize {
+    hero * 169 - hero * (hero + 1) / 2 + villain
+}
+
+/// Preflop equities worked out exactly, by dealing every board.
+pub struct ExactEquity;
+
+impl PreflopEquity for ExactEquity {
+    fn equity(&self, hero: HandClass, villain: HandClass) -> f32 {
+        exact_equity(hero, villain)
+    }
+}
+
+/// `hero`'s equity against `villain` over every pair of combos they can hold at once and every board.
+/// Matchups that only differ by which suit is which play out the same, so each is dealt out once.
+pub fn exact_equity(hero: HandClass, villain: HandClass) -> f32 {
+    let matchups = matchups(hero, villain);
+    if matchups.is_empty() {
+        return 0.5;
+    }
+    let (won, dealt) = matchups.iter().fold((0.0f64, 0.0f64), |(won, dealt), &(((a, b), (c, d)), count)| {
+        (won + count as f64 * matchup_equity((a, b), (c, d), &vec![]), dealt + count as f64)
+    });
+    (won / dealt) as f32
+}
+
+/// The equity of `mine` against `theirs` over every way the rest of `board` can come.
+fn matchup_equity(mine: (Card, Card), theirs: (Card, Card), board: &Vec<Card>) -> f64 {
+    let mine = Hand::new(vec![mine.0, mine.1]);
+    let theirs = Hand::new(vec![theirs.0, theirs.1]);
+    let deck = (whole_deck() - mine.clone() - theirs.clone() - Hand::new(board.clone())).cards();
+    let mut won = 0.0f64;
+    let mut boards = 0.0f64;
+    each_board(deck.as_slice(), &mut board.clone(), 0, &mut |board: &Vec<Card>| {
+        let board = Hand::new(board.clone());
+        won += match (mine.clone() + board.clone()).cmp(&(theirs.clone() + board)) {
+            Ordering::Greater => 1.0,
+            Ordering::Equal => 0.5,
+            Ordering::Less => 0.0,
+        };
+        boards += 1.0;
+    });
+    won / boards
+}
+
+/// The pairs of combos `hero` and `villain` can hold without sharing a card, one of each kind up to
+/// renaming the suits, with how many there are of that kind.
+fn matchups(hero: HandClass, villain: HandClass) -> Vec<(((Card, Card), (Card, Card)), usize)> {
+    let mut kinds: HashMap<Vec<(Value, usize)>, usize> = HashMap::new();
+    let mut matchups = vec![];
+    for &(a, b) in hero.combos().iter() {
+        for &(c, d) in villain.combos().iter() {
+            if a == c || a == d || b == c || b == d {
+                continue;
+            }
+            let kind = suit_pattern(&[a, b, c, d]);
+            match kinds.get(&kind) {
+                Some(&at) => {
+                    let (matchup, count) = matchups[at];
+                    matchups[at] = (matchup, count + 1);
+                    continue;
+                },
+                None => (),
+            }
+            kinds.insert(kind, matchups.len());
+            matchups.push((((a, b), (c, d)), 1));
+        }
+    }
+    matchups
+}
+
+/// The cards with each suit replaced by the order it first turns up in.
+fn suit_pattern(cards: &[Card]) -> Vec<(Value, usize)> {
+    let mut seen: Vec<Suit> = vec![];
+    cards.iter()
+         .map(|card| {
+             let suit = match seen.iter().position(|suit| suit == card.suit()) {
+                 Some(position) => position,
+                 None => {
+                     seen.push(*card.suit());
+                     seen.len() - 1
+                 },
+             };
+             (*card.value(), suit)
+         })
+         .collect()
+}
+
+/// Calls `deal` with every way of filling `board` out to five cards from `deck`.
+fn each_board<F: FnMut(&Vec<Card>)>(deck: &[Card], board: &mut Vec<Card>, from: usize, deal: &mut F) {
+    if board.len() == 5 {
+        deal(board);
+        return;
+    }
+    for index in range(from, deck.len() + board.len() + 1 - 5) {
+        board.push(deck[index]);
+        each_board(deck, board, index + 1, deal);
+        board.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{matchups, matchup_equity, each_board, PreflopMatrix, PreflopTableErr, TABLE_VERSION};
+    use card::Card;
+    use hand::Hand;
+    use pushfold::PreflopEquity;
+    use range::HandClass;
+    use std::old_io::MemReader;
+
+    /// A cheap stand-in for the exact equities, different for every pair of classes.
+    struct ByIndex;
+
+    impl PreflopEquity for ByIndex {
+        fn equity(&self, hero: HandClass, villain: HandClass) -> f32 {
+            0.5 + (villain.index() as f32 - hero.index() as f32) / 400.0
+        }
+    }
+
+    fn class(s: &str) -> HandClass {
+        s.parse().ok().unwrap()
+    }
+
+    #[test] fn equity_the_other_way_round_is_the_rest() {
+        let matrix = PreflopMatrix::tabulate(&ByIndex);
+        assert_eq!(matrix.lookup(class("AA"), class("72o")), ByIndex.equity(class("AA"), class("72o")));
+        assert_eq!(matrix.lookup(class("72o"), class("AA")), 1.0 - matrix.lookup(class("AA"), class("72o")));
+    }
+
+    #[test] fn tables_survive_being_written_and_read() {
+        let matrix = PreflopMatrix::tabulate(&ByIndex);
+        let mut bytes = vec![];
+        matrix.write(&mut bytes).ok().unwrap();
+        assert_eq!(bytes.len(), 5 + 2 * 14365);
+        let read = PreflopMatrix::read(&mut MemReader::new(bytes)).ok().unwrap();
+        for hero in HandClass::all().into_iter() {
+            for villain in HandClass::all().into_iter() {
+                assert!((read.lookup(hero, villain) - matrix.lookup(hero, villain)).abs() < 0.0001);
+            }
+        }
+    }
+
+    #[test] fn other_files_and_versions_are_refused() {
+        let mut bytes = vec![];
+        PreflopMatrix::tabulate(&ByIndex).write(&mut bytes).ok().unwrap();
+        let mut newer = bytes.clone();
+        newer[4] = TABLE_VERSION + 1;
+        assert_eq!(PreflopMatrix::read(&mut MemReader::new(newer)), Err(PreflopTableErr::UnsupportedVersion(TABLE_VERSION + 1)));
+        assert_eq!(PreflopMatrix::read(&mut MemReader::new(b"PNG!".to_vec())), Err(PreflopTableErr::NotATable));
+        bytes.truncate(1000);
+        assert_eq!(PreflopMatrix::read(&mut MemReader::new(bytes)), Err(PreflopTableErr::Unreadable));
+    }
+
+    #[test] fn the_shipped_table_has_the_exact_equities() {
+        let matrix = PreflopMatrix::exact();
+        assert!((matrix.lookup(class("AA"), class("KK")) - 0.8195).abs() < 0.001);
+        assert!((matrix.lookup(class("QQ"), class("AKo")) - 0.5676).abs() < 0.001);
+        assert!((matrix.lookup(class("72o"), class("AA")) - 0.1180).abs() < 0.001);
+        assert!((matrix.lookup(class("T9s"), class("T9s")) - 0.5).abs() < 0.0001);
+    }
+
+    #[test] fn aces_lose_only_to_the_last_king() {
+        let card = |s: &str| s.parse::<Card>().ok().unwrap();
+        let board = "KD 7C 2D 3S".parse::<Hand>().ok().unwrap().cards();
+        let equity = matchup_equity((card("AS"), card("AH")), (card("KS"), card("KH")), &board);
+        assert!((equity - 43.0 / 44.0).abs() < 1e-9);
+    }
+
+    #[test] fn matchups_cover_every_pair_of_combos_once() {
+        let count = |hero: &str, villain: &str| matchups(class(hero), class(villain)).iter().fold(0, |total, &(_, count)| total + count);
+        assert_eq!(count("AA", "KK"), 36);
+        assert_eq!(count("AKs", "AKs"), 12);
+        assert_eq!(count("AKo", "AA"), 36);
+        assert!(matchups(class("AA"), class("KK")).len() < 36);
+    }
+
+    #[test] fn every_board_is_dealt_once() {
+        let deck = "AS KS QS JS 0S 9S 8S".parse::<Hand>().ok().unwrap().cards();
+        let mut boards: Vec<Vec<Card>> = vec![];
+        each_board(deck.as_slice(), &mut vec![], 0, &mut |board: &Vec<Card>| boards.push(board.clone()));
+        assert_eq!(boards.len(), 21);
+        boards.sort();
+        boards.dedup();
+        assert_eq!(boards.len(), 21);
+    }
+}
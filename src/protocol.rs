@@ -0,0 +1,188 @@
+use card::{Card, Notation};
+use history::Blind;
+use table::{HandState, Street, Action};
+
+use std::iter::repeat;
+
+/// What the player in seat `position` is told about `hand`, the `hand_number`th of the match, in the
+/// style of the ACPC protocol: "MATCHSTATE:position:hand:betting:cards".
+/// The betting goes street by street, split by "/", with "f" to fold, "c" to check or call, and "r"
+/// followed by everything the player has put in the hand so far to bet or raise. The cards are each
+/// seat's hole cards split by "|", blank where the player can't see them, then the board by street.
+pub fn match_state(hand: &HandState, position: usize, hand_number: usize) -> String {
+    format!("MATCHSTATE:{}:{}:{}:{}", position, hand_number, betting(hand), cards(hand, position))
+}
+
+fn betting(hand: &HandState) -> String {
+    let history = hand.history();
+    let mut put_in = repeat(0).take(history.seats.len()).collect::<Vec<u32>>();
+    for post in history.posts.iter() {
+        put_in[post.seat] += post.amount;
+    }
+    let mut streets = repeat(String::new()).take(streets_started(hand)).collect::<Vec<String>>();
+    for action in history.actions.iter() {
+        put_in[action.seat] += action.amount;
+        let written = match action.action {
+            Action::Fold => "f".to_string(),
+            Action::Check | Action::Call => "c".to_string(),
+            Action::Bet(_) | Action::Raise(_) => format!("r{}", put_in[action.seat]),
+        };
+        streets[street_index(action.street)].push_str(written.as_slice());
+    }
+    streets.connect("/")
+}
+
+fn cards(hand: &HandState, position: usize) -> String {
+    let shown = hand.showdown().into_iter().map(|(seat, _)| seat).collect::<Vec<usize>>();
+    let holes = range(0, hand.stacks().len())
+                .map(|seat| match seat == position || shown.contains(&seat) {
+                    true => hand.hole_cards(seat).cards().iter().map(|card| card_text(card)).collect::<Vec<String>>().concat(),
+                    false => String::new(),
+                })
+                .collect::<Vec<String>>();
+    let board = hand.board().cards();
+    let mut text = holes.connect("|");
+    for &(from, to) in [(0, 3), (3, 4), (4, 5)].iter() {
+        if board.len() >= to {
+            text.push('/');
+            text.push_str(board[from..to].iter().map(|card| card_text(card)).collect::<Vec<String>>().concat().as_slice());
+        }
+    }
+    text
+}
+
+/// How many streets have started, going by the board.
+fn streets_started(hand: &HandState) -> usize {
+    match hand.board().cards().len() {
+        0 => 1,
+        3 => 2,
+        4 => 3,
+        _ => 4,
+    }
+}
+
+fn street_index(street: Street) -> usize {
+    match street {
+        Street::Preflop => 0,
+        Street::Flop => 1,
+        Street::Turn => 2,
+        _ => 3,
+    }
+}
+
+/// Cards the way ACPC writes them, like "Th" or "2c".
+fn card_text(card: &Card) -> String {
+    card.notated(Notation::Standard).to_string()
+}
+
+/// Whether `reply` answers `state`: the state itself, then ":" and an action.
+pub fn is_reply_to(reply: &str, state: &str) -> bool {
+    let reply = reply.trim();
+    reply.len() > state.len() && reply.starts_with(state) && reply[state.len()..].starts_with(":")
+}
+
+/// The action in a player's reply to `state`, which is `state` with ":" and their action on the end.
+/// A raise gives the total the player wants to have put in the hand. `None` if the reply is to some
+/// other state or doesn't make sense.
+pub fn parse_action(reply: &str, state: &str, hand: &HandState) -> Option<Action> {
+    let seat = match hand.to_act() {
+        Some(seat) => seat,
+        None => return None,
+    };
+    if !is_reply_to(reply, state) {
+        return None;
+    }
+    let action = &reply.trim()[state.len() + 1..];
+    match action.chars().next() {
+        Some('f') if action.len() == 1 => Some(Action::Fold),
+        Some('c') if action.len() == 1 => Some(match hand.to_call() {
+            0 => Action::Check,
+            _ => Action::Call,
+        }),
+        Some('r') => {
+            let total = match action[1..].parse::<u32>() {
+                Ok(total) => total,
+                Err(_) => return None,
+            };
+            let before = put_in_before_street(hand, seat);
+            if total < before {
+                return None;
+            }
+            Some(match hand.legal_actions().iter().any(|action| match *action { Action::Bet(_) => true, _ => false }) {
+                true => Action::Bet(total - before),
+                false => Action::Raise(total - before),
+            })
+        },
+        _ => None,
+    }
+}
+
+/// What `seat` put in before the current street's betting, which a raise's total doesn't count towards
+/// the street's bet. Blinds count towards the preflop bet; antes don't.
+fn put_in_before_street(hand: &HandState, seat: usize) -> u32 {
+    let history = hand.history();
+    let street = street_index(hand.street());
+    let posted = history.posts.iter()
+                 .filter(|post| post.seat == seat && (post.blind == Blind::Ante || street > 0))
+                 .fold(0, |total, post| total + post.amount);
+    history.actions.iter()
+        .filter(|action| action.seat == seat && street_index(action.street) < street)
+        .fold(posted, |total, action| total + action.amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{match_state, parse_action, is_reply_to};
+    use table::{Table, HandState, Action, stacked_deck};
+
+    fn new_hand() -> HandState {
+        HandState::new(&Table::new(vec![100, 100], 1, 2), stacked_deck("AS KD AH KC 2C 7D 8H 9C 3S JD 4C 5H")).ok().unwrap()
+    }
+
+    #[test] fn players_only_see_their_own_cards() {
+        let mut hand = new_hand();
+        assert_eq!(match_state(&hand, 0, 3), "MATCHSTATE:0:3::KdKc|");
+        hand.act(Action::Raise(6)).ok().unwrap();
+        assert_eq!(match_state(&hand, 1, 3), "MATCHSTATE:1:3:r6:|AsAh");
+    }
+
+    #[test] fn streets_are_split_and_raises_count_the_whole_hand() {
+        let mut hand = new_hand();
+        hand.act(Action::Raise(6)).ok().unwrap();
+        hand.act(Action::Call).ok().unwrap();
+        hand.act(Action::Bet(10)).ok().unwrap();
+        let state = match_state(&hand, 0, 0);
+        let fields = state.split(':').collect::<Vec<&str>>();
+        assert_eq!(fields[3], "r6c/r16");
+        assert_eq!(fields[4].split('/').count(), 2);
+    }
+
+    #[test] fn replies_end_with_the_action() {
+        let mut hand = new_hand();
+        let state = match_state(&hand, 0, 0);
+        assert_eq!(parse_action("MATCHSTATE:0:0::KdKc|:c", state.as_slice(), &hand), Some(Action::Call));
+        assert_eq!(parse_action("MATCHSTATE:0:0::KdKc|:r6\r\n", state.as_slice(), &hand), Some(Action::Raise(6)));
+        assert_eq!(parse_action("MATCHSTATE:0:0::KdKc|:x", state.as_slice(), &hand), None);
+        hand.act(Action::Raise(6)).ok().unwrap();
+        hand.act(Action::Call).ok().unwrap();
+        let state = match_state(&hand, 1, 0);
+        let reply = |action: &str| format!("{}:{}", state, action);
+        assert_eq!(parse_action(reply("r16").as_slice(), state.as_slice(), &hand), Some(Action::Bet(10)));
+        assert_eq!(parse_action(reply("c").as_slice(), state.as_slice(), &hand), Some(Action::Check));
+    }
+
+    #[test] fn replies_to_other_states_are_refused() {
+        let mut hand = new_hand();
+        let earlier = match_state(&hand, 0, 0);
+        hand.act(Action::Raise(6)).ok().unwrap();
+        let state = match_state(&hand, 1, 0);
+        // A reply to the state before, arriving late, mustn't be taken as the answer to this one.
+        let late = format!("{}:c", earlier);
+        assert!(!is_reply_to(late.as_slice(), state.as_slice()));
+        assert_eq!(parse_action(late.as_slice(), state.as_slice(), &hand), None);
+        let next_hand = format!("{}:c", match_state(&hand, 1, 1));
+        assert_eq!(parse_action(next_hand.as_slice(), state.as_slice(), &hand), None);
+        assert!(!is_reply_to(state.as_slice(), state.as_slice()));
+        assert!(is_reply_to(format!("{}:f", state).as_slice(), state.as_slice()));
+    }
+}
@@ -0,0 +1,389 @@
+use calculations::whole_deck;
+use hand::Hand;
+use icm;
+use range::{HandClass, Range};
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::iter::repeat;
+use std::rand::{thread_rng, Rng};
+
+/// How often one hand class beats another by the river, all in before the flop, with ties counting half.
+pub trait PreflopEquity {
+    fn equity(&self, hero: HandClass, villain: HandClass) -> f32;
+}
+
+/// Preflop equities worked out by dealing out boards, and remembered once they have been. For when
+/// there's no `preflop::PreflopMatrix` table to hand.
+pub struct SampledEquity {
+    samples: usize,
+    known: RefCell<HashMap<(usize, usize), f32>>,
+}
+
+impl SampledEquity {
+    pub fn new(samples: usize) -> SampledEquity {
+        SampledEquity { samples: samples, known: RefCell::new(HashMap::new()) }
+    }
+}
+
+impl PreflopEquity for SampledEquity {
+    fn equity(&self, hero: HandClass, villain: HandClass) -> f32 {
+        let key = (hero.index(), villain.index());
+        match self.known.borrow().get(&key) {
+            Some(&equity) => return equity,
+            None => (),
+        }
+        let equity = sample_equity(hero, villain, self.samples);
+        self.known.borrow_mut().insert(key, equity);
+        self.known.borrow_mut().insert((villain.index(), hero.index()), 1.0 - equity);
+        equity
+    }
+}
+
+/// Deals `samples` boards, going through every pair of combos the two classes can hold at once in turn.
+pub fn sample_equity(hero: HandClass, villain: HandClass, samples: usize) -> f32 {
+    let mut rng = thread_rng();
+    let matchups = hero.combos().into_iter()
+                       .flat_map(|mine| villain.combos().into_iter().map(move |theirs| (mine, theirs)))
+                       .filter(|&((a, b), (c, d))| a != c && a != d && b != c && b != d)
+                       .collect::<Vec<_>>();
+    if matchups.is_empty() {
+        return 0.5;
+    }
+    let won = range(0, samples).fold(0.0, |won, sample| {
+        let ((a, b), (c, d)) = matchups[sample % matchups.len()];
+        let mine = Hand::new(vec![a, b]);
+        let theirs = Hand::new(vec![c, d]);
+        let mut deck_cards = (whole_deck() - mine.clone() - theirs.clone()).cards();
+        rng.shuffle(deck_cards.as_mut_slice());
+        let board = Hand::new(deck_cards.into_iter().take(5).collect());
+        let (mine, theirs) = (mine + board.clone(), theirs + board);
+        won + match mine.cmp(&theirs) {
+            Ordering::Greater => 1.0,
+            Ordering::Equal => 0.5,
+            Ordering::Less => 0.0,
+        }
+    });
+    won / samples as f32
+}
+
+/// A spot where everyone can only move all in or fold. Stacks are in order of action before the flop,
+/// so the last two are the small and big blinds; heads-up, the first is the button in the small blind.
+/// Outcomes are valued in chips, or by ICM when the spot has payouts.
+#[derive(Debug, Clone)]
+pub struct PushFoldSpot {
+    stacks: Vec<u32>,
+    small_blind: u32,
+    big_blind: u32,
+    ante: u32,
+    payouts: Option<Vec<f32>>,
+}
+
+impl PushFoldSpot {
+    pub fn new(stacks: Vec<u32>, small_blind: u32, big_blind: u32) -> PushFoldSpot {
+        PushFoldSpot { stacks: stacks, small_blind: small_blind, big_blind: big_blind, ante: 0, payouts: None }
+    }
+
+    pub fn with_ante(mut self, ante: u32) -> PushFoldSpot {
+        self.ante = ante;
+        self
+    }
+
+    pub fn with_icm(mut self, payouts: Vec<f32>) -> PushFoldSpot {
+        self.payouts = Some(payouts);
+        self
+    }
+
+    fn posted(&self) -> Vec<u32> {
+        let players = self.stacks.len();
+        range(0, players)
+            .map(|player| {
+                let blind = match players - player {
+                    2 => self.small_blind,
+                    1 => self.big_blind,
+                    _ => 0,
+                };
+                let owed = self.ante + blind;
+                if owed < self.stacks[player] { owed } else { self.stacks[player] }
+            })
+            .collect()
+    }
+
+    /// What every player's hand is worth once the stacks are `finishing`.
+    fn values(&self, finishing: Vec<u32>) -> Vec<f32> {
+        match self.payouts {
+            Some(ref payouts) => icm::equities(&finishing, payouts),
+            None => finishing.iter().zip(self.stacks.iter()).map(|(&after, &before)| after as f32 - before as f32).collect(),
+        }
+    }
+
+    /// `pusher` takes the blinds and antes with nobody calling.
+    fn steal(&self, pusher: usize) -> Vec<f32> {
+        let posted = self.posted();
+        let pot = posted.iter().fold(0, |pot, &chips| pot + chips);
+        let finishing = range(0, self.stacks.len())
+                        .map(|player| match player == pusher {
+                            true => self.stacks[player] - posted[player] + pot,
+                            false => self.stacks[player] - posted[player],
+                        })
+                        .collect();
+        self.values(finishing)
+    }
+
+    /// `winner` and `loser` got it all in and `winner` won.
+    fn showdown(&self, winner: usize, loser: usize) -> Vec<f32> {
+        let posted = self.posted();
+        let matched = if self.stacks[winner] < self.stacks[loser] { self.stacks[winner] } else { self.stacks[loser] };
+        let dead = range(0, self.stacks.len())
+                   .filter(|&player| player != winner && player != loser)
+                   .fold(0, |dead, player| dead + posted[player]);
+        let finishing = range(0, self.stacks.len())
+                        .map(|player| {
+                            if player == winner {
+                                self.stacks[player] + matched + dead
+                            } else if player == loser {
+                                self.stacks[player] - matched
+                            } else {
+                                self.stacks[player] - posted[player]
+                            }
+                        })
+                        .collect();
+        self.values(finishing)
+    }
+}
+
+/// Who pushes when folded to, and who calls each pusher when folded to, as ranges.
+#[derive(Debug, Clone)]
+pub struct PushFoldSolution {
+    pushing: Vec<Range>,
+    calling: Vec<Vec<Range>>,
+}
+
+impl PushFoldSolution {
+    /// `player`'s pushing range when everyone before them has folded. The big blind never gets to push.
+    pub fn pushing(&self, player: usize) -> &Range {
+        &self.pushing[player]
+    }
+
+    /// `caller`'s calling range against `pusher`, with everyone between them having folded.
+    /// `None` unless `caller` acts after `pusher`.
+    pub fn calling(&self, pusher: usize, caller: usize) -> Option<&Range> {
+        match (self.calling.get(pusher), caller > pusher) {
+            (Some(callers), true) => callers.get(caller - pusher - 1),
+            _ => None,
+        }
+    }
+}
+
+/// Works out push/fold ranges by fictitious play: every player keeps answering the others' average
+/// ranges so far with their best response, and the averages settle on an equilibrium.
+/// With more than two players only the first caller plays against the pusher; overcalls aren't considered.
+/// Card removal between players is ignored.
+pub fn solve<E: PreflopEquity>(spot: &PushFoldSpot, equities: &E, iterations: usize) -> PushFoldSolution {
+    let players = spot.stacks.len();
+    let pushers = players - 1;
+    let steals = range(0, pushers).map(|pusher| spot.steal(pusher)).collect::<Vec<Vec<f32>>>();
+    let showdowns = range(0, players)
+                    .map(|winner| range(0, players).map(|loser| match winner == loser {
+                        true => vec![],
+                        false => spot.showdown(winner, loser),
+                    }).collect::<Vec<Vec<f32>>>())
+                    .collect::<Vec<Vec<Vec<f32>>>>();
+    // Folding round to the big blind, which is all anyone who folds is assumed to care about.
+    let walk = spot.steal(players - 1);
+
+    let mut pushing = repeat(Range::full()).take(pushers).collect::<Vec<Range>>();
+    let mut calling = range(0, pushers)
+                      .map(|pusher| repeat(Range::full()).take(players - pusher - 1).collect())
+                      .collect::<Vec<Vec<Range>>>();
+    for iteration in range(0, iterations) {
+        let mut best_pushing = vec![];
+        let mut best_calling = vec![];
+        for pusher in range(0, pushers) {
+            best_pushing.push(best_response(|class| {
+                let push = first_caller_value(pusher, pusher, calling[pusher].as_slice(), &showdowns, &steals[pusher],
+                                              |caller_range| class_equity(equities, class, caller_range));
+                (push, walk[pusher])
+            }));
+            let push_range = &pushing[pusher];
+            best_calling.push(range(pusher + 1, players)
+                .map(|caller| {
+                    let later = &calling[pusher][caller - pusher..];
+                    let fold = later_callers_value(caller, pusher, caller, later, &showdowns, &steals[pusher],
+                                                   |caller_range| range_equity(equities, push_range, caller_range));
+                    best_response(|class| {
+                        let call = match class_equity(equities, class, push_range) {
+                            Some(equity) => equity * showdowns[caller][pusher][caller] +
+                                (1.0 - equity) * showdowns[pusher][caller][caller],
+                            None => fold,
+                        };
+                        (call, fold)
+                    })
+                })
+                .collect::<Vec<Range>>());
+        }
+        let step = 1.0 / (iteration + 2) as f32;
+        for (average, best) in pushing.iter_mut().zip(best_pushing.iter()) {
+            *average = mix(average, best, step);
+        }
+        for (averages, bests) in calling.iter_mut().zip(best_calling.iter()) {
+            for (average, best) in averages.iter_mut().zip(bests.iter()) {
+                *average = mix(average, best, step);
+            }
+        }
+    }
+    PushFoldSolution { pushing: pushing, calling: calling }
+}
+
+/// The range of classes for which the first value, for going in, beats the second, for folding.
+fn best_response<F: Fn(HandClass) -> (f32, f32)>(values: F) -> Range {
+    let mut best = Range::empty();
+    for class in HandClass::all().into_iter() {
+        let (going_in, folding) = values(class);
+        if going_in > folding {
+            best.set_weight(class, 1.0);
+        }
+    }
+    best
+}
+
+/// `player`'s value from `pusher` going all in, with each of `callers` (the players after `pusher`,
+/// in order) calling with their range until one does.
+/// `equity` gives how often the pusher beats a calling range.
+fn first_caller_value<F: Fn(&Range) -> Option<f32>>(player: usize, pusher: usize, callers: &[Range],
+                                                    showdowns: &Vec<Vec<Vec<f32>>>, steal: &Vec<f32>, equity: F) -> f32 {
+    later_callers_value(player, pusher, pusher, callers, showdowns, steal, equity)
+}
+
+/// Like `first_caller_value`, for the callers after `after`.
+fn later_callers_value<F: Fn(&Range) -> Option<f32>>(player: usize, pusher: usize, after: usize, callers: &[Range],
+                                                     showdowns: &Vec<Vec<Vec<f32>>>, steal: &Vec<f32>, equity: F) -> f32 {
+    let mut reach = 1.0;
+    let mut value = 0.0;
+    for (offset, caller_range) in callers.iter().enumerate() {
+        let caller = after + 1 + offset;
+        let calls = caller_range.fraction();
+        let equity = match equity(caller_range) {
+            Some(equity) => equity,
+            None => continue,
+        };
+        value += reach * calls * (equity * showdowns[pusher][caller][player] + (1.0 - equity) * showdowns[caller][pusher][player]);
+        reach *= 1.0 - calls;
+    }
+    value + reach * steal[player]
+}
+
+/// How often `class` beats `range`, or `None` for an empty range.
+fn class_equity<E: PreflopEquity>(equities: &E, class: HandClass, range: &Range) -> Option<f32> {
+    let (won, combos) = range.classes().iter().fold((0.0, 0.0), |(won, combos), &villain| {
+        let weight = range.weight(villain) * villain.combo_count() as f32;
+        (won + weight * equities.equity(class, villain), combos + weight)
+    });
+    match combos > 0.0 {
+        true => Some(won / combos),
+        false => None,
+    }
+}
+
+/// How often `hero` beats `villain`, or `None` if either is empty.
+fn range_equity<E: PreflopEquity>(equities: &E, hero: &Range, villain: &Range) -> Option<f32> {
+    let (won, combos) = hero.classes().iter().fold((0.0, 0.0), |(won, combos), &class| {
+        match class_equity(equities, class, villain) {
+            Some(equity) => {
+                let weight = hero.weight(class) * class.combo_count() as f32;
+                (won + weight * equity, combos + weight)
+            },
+            None => (won, combos),
+        }
+    });
+    match combos > 0.0 {
+        true => Some(won / combos),
+        false => None,
+    }
+}
+
+fn mix(average: &Range, best: &Range, step: f32) -> Range {
+    let mut mixed = Range::empty();
+    for class in HandClass::all().into_iter() {
+        mixed.set_weight(class, average.weight(class) + (best.weight(class) - average.weight(class)) * step);
+    }
+    mixed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve, sample_equity, PreflopEquity, PushFoldSpot, SampledEquity};
+    use range::HandClass;
+    use std::num::Float;
+
+    /// Stands in for real equities: better classes win more, by how far apart they are.
+    struct ByStrength;
+
+    fn strength(class: HandClass) -> f32 {
+        let pair = match class.is_pair() { true => 14.0, false => 0.0 };
+        let suited = match class.is_suited() { true => 1.0, false => 0.0 };
+        pair + class.high() as usize as f32 + class.low() as usize as f32 / 2.0 + suited
+    }
+
+    impl PreflopEquity for ByStrength {
+        fn equity(&self, hero: HandClass, villain: HandClass) -> f32 {
+            let difference = (strength(hero) - strength(villain)) / 60.0;
+            0.5 + if difference > 0.35 { 0.35 } else if difference < -0.35 { -0.35 } else { difference }
+        }
+    }
+
+    fn class(s: &str) -> HandClass {
+        s.parse().ok().unwrap()
+    }
+
+    #[test] fn short_stacks_push_wider() {
+        let short = solve(&PushFoldSpot::new(vec![300, 300], 50, 100), &ByStrength, 60);
+        let deep = solve(&PushFoldSpot::new(vec![3000, 3000], 50, 100), &ByStrength, 60);
+        assert!(short.pushing(0).fraction() > deep.pushing(0).fraction());
+    }
+
+    #[test] fn the_best_hands_push_and_call() {
+        let solution = solve(&PushFoldSpot::new(vec![2000, 2000], 50, 100), &ByStrength, 60);
+        assert!(solution.pushing(0).weight(class("AA")) > 0.95);
+        assert!(solution.calling(0, 1).unwrap().weight(class("AA")) > 0.95);
+        assert!(solution.calling(0, 1).unwrap().weight(class("72o")) < 0.05);
+    }
+
+    #[test] fn calling_ranges_are_tighter_than_pushing_ranges() {
+        let solution = solve(&PushFoldSpot::new(vec![1000, 1000], 50, 100), &ByStrength, 60);
+        assert!(solution.calling(0, 1).unwrap().fraction() < solution.pushing(0).fraction());
+    }
+
+    #[test] fn later_positions_push_wider() {
+        let solution = solve(&PushFoldSpot::new(vec![1000, 1000, 1000], 50, 100).with_ante(10), &ByStrength, 40);
+        assert!(solution.pushing(1).fraction() > solution.pushing(0).fraction());
+    }
+
+    #[test] fn icm_tightens_calls_near_the_money() {
+        let spot = PushFoldSpot::new(vec![1000, 1000, 1000], 50, 100);
+        let chips = solve(&spot, &ByStrength, 40);
+        let money = solve(&spot.clone().with_icm(vec![50.0, 30.0, 20.0]), &ByStrength, 40);
+        assert!(money.calling(0, 2).unwrap().fraction() < chips.calling(0, 2).unwrap().fraction());
+    }
+
+    #[test] fn only_later_players_call() {
+        let solution = solve(&PushFoldSpot::new(vec![1000, 1000, 1000], 50, 100), &ByStrength, 5);
+        assert!(solution.calling(1, 2).is_some());
+        assert!(solution.calling(1, 1).is_none());
+        assert!(solution.calling(2, 0).is_none());
+        assert!(solution.calling(0, 3).is_none());
+    }
+
+    #[test] fn aces_beat_kings_about_four_times_in_five() {
+        assert!((sample_equity(class("AA"), class("KK"), 2000) - 0.82).abs() < 0.04);
+    }
+
+    #[test] fn sampled_equities_solve_to_sensible_ranges() {
+        let solution = solve(&PushFoldSpot::new(vec![3000, 3000], 50, 100), &SampledEquity::new(4), 3);
+        assert!(solution.pushing(0).weight(class("AA")) > 0.95);
+        assert!(solution.calling(0, 1).unwrap().weight(class("AA")) > 0.95);
+        // Never a best response, so after three rounds of averaging only the starting quarter is left.
+        assert!(solution.calling(0, 1).unwrap().weight(class("72o")) < 0.3);
+    }
+}
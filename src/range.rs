@@ -0,0 +1,376 @@
+use card::{Card, Value, Suit};
+use card::Value::*;
+
+use std::iter::repeat;
+use std::str::FromStr;
+
+/// Values from the top of a range chart down, so index 0 is the ace.
+static VALUES: [Value; 13] = [Ace, King, Queen, Jack, Ten, Nine, Eight, Seven, Six, Five, Four, Three, Two];
+static SUITS: [Suit; 4] = [Suit::Diamond, Suit::Club, Suit::Heart, Suit::Spade];
+
+pub static TOTAL_COMBOS: usize = 1326;
+
+/// Two hole cards with the suits forgotten, like "AKs", "AKo" or "QQ". There are 169 of them.
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct HandClass {
+    high: Value,
+    low: Value,
+    suited: bool,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum RangeParseErr {
+    BadClass,
+    BadWeight,
+}
+
+impl HandClass {
+    /// Pairs are never suited, whatever `suited` says. `None` for a joker, which isn't in any class.
+    pub fn new(first: Value, second: Value, suited: bool) -> Option<HandClass> {
+        match (chart_position(&first), chart_position(&second)) {
+            (Some(first), Some(second)) => Some(HandClass::at(first, second, suited)),
+            _ => None,
+        }
+    }
+
+    pub fn of(first: &Card, second: &Card) -> Option<HandClass> {
+        HandClass::new(*first.value(), *second.value(), first.suit() == second.suit())
+    }
+
+    /// The class of the values at two chart positions.
+    fn at(first: usize, second: usize, suited: bool) -> HandClass {
+        let (high, low) = match first <= second {
+            true => (VALUES[first], VALUES[second]),
+            false => (VALUES[second], VALUES[first]),
+        };
+        HandClass { high: high, low: low, suited: suited && high != low }
+    }
+
+    pub fn all() -> Vec<HandClass> {
+        range(0, 169).map(|index| HandClass::from_index(index)).collect()
+    }
+
+    /// Where the class sits in the usual 13 by 13 chart, read row by row from the top left:
+    /// pairs on the diagonal, suited hands above it and offsuit hands below.
+    pub fn index(&self) -> usize {
+        // Classes never hold jokers, so both values have a position.
+        let (high, low) = (chart_position(&self.high).unwrap(), chart_position(&self.low).unwrap());
+        match self.suited {
+            true => high * 13 + low,
+            false => low * 13 + high,
+        }
+    }
+
+    pub fn from_index(index: usize) -> HandClass {
+        let (row, column) = (index / 13, index % 13);
+        HandClass::at(row, column, row < column)
+    }
+
+    pub fn high(&self) -> Value {
+        self.high
+    }
+
+    pub fn low(&self) -> Value {
+        self.low
+    }
+
+    pub fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    pub fn is_suited(&self) -> bool {
+        self.suited
+    }
+
+    /// Every pair of cards in the class.
+    pub fn combos(&self) -> Vec<(Card, Card)> {
+        let mut combos = vec![];
+        for (first_index, &first) in SUITS.iter().enumerate() {
+            for (second_index, &second) in SUITS.iter().enumerate() {
+                let wanted = match (self.is_pair(), self.suited) {
+                    (true, _) => first_index < second_index,
+                    (false, true) => first == second,
+                    (false, false) => first != second,
+                };
+                if wanted {
+                    combos.push((Card::new(self.high, first), Card::new(self.low, second)));
+                }
+            }
+        }
+        combos
+    }
+
+    pub fn combo_count(&self) -> usize {
+        match (self.is_pair(), self.suited) {
+            (true, _) => 6,
+            (false, true) => 4,
+            (false, false) => 12,
+        }
+    }
+
+    pub fn to_notation(&self) -> String {
+        let suitedness = match (self.is_pair(), self.suited) {
+            (true, _) => "",
+            (false, true) => "s",
+            (false, false) => "o",
+        };
+        format!("{}{}{}", value_char(&self.high), value_char(&self.low), suitedness)
+    }
+}
+
+impl FromStr for HandClass {
+    type Err = RangeParseErr;
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        match parse_classes(s) {
+            Ok(ref classes) if classes.len() == 1 => Ok(classes[0]),
+            _ => Err(RangeParseErr::BadClass),
+        }
+    }
+}
+
+/// How much of each hand class a player holds, from 0 to 1.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Range {
+    weights: Vec<f32>,
+}
+
+impl Range {
+    pub fn empty() -> Range {
+        Range { weights: repeat(0.0).take(169).collect() }
+    }
+
+    pub fn full() -> Range {
+        Range { weights: repeat(1.0).take(169).collect() }
+    }
+
+    pub fn weight(&self, class: HandClass) -> f32 {
+        self.weights[class.index()]
+    }
+
+    pub fn set_weight(&mut self, class: HandClass, weight: f32) {
+        self.weights[class.index()] = weight;
+    }
+
+    pub fn contains(&self, class: HandClass) -> bool {
+        self.weight(class) > 0.0
+    }
+
+    pub fn classes(&self) -> Vec<HandClass> {
+        HandClass::all().into_iter().filter(|&class| self.contains(class)).collect()
+    }
+
+    /// How many two-card combinations the range holds, counting partly held classes in part.
+    pub fn combos(&self) -> f32 {
+        HandClass::all().iter().fold(0.0, |combos, class| combos + self.weight(*class) * class.combo_count() as f32)
+    }
+
+    /// The share of all starting hands the range holds.
+    pub fn fraction(&self) -> f32 {
+        self.combos() / TOTAL_COMBOS as f32
+    }
+
+    /// The range written the way range charts write it, like "77+, A2s+, KTo+, 65s:0.5".
+    pub fn to_notation(&self) -> String {
+        let mut items = vec![];
+        // Pairs, from aces down.
+        items.extend(self.runs(range(0, 13).map(|rank| HandClass::at(rank, rank, false)).collect()).into_iter());
+        for high in range(0, 12) {
+            for &suited in [true, false].iter() {
+                let classes = range(high + 1, 13).map(|low| HandClass::at(high, low, suited)).collect();
+                items.extend(self.runs(classes).into_iter());
+            }
+        }
+        items.connect(", ")
+    }
+
+    /// Items for the runs of equal weight among `classes`, which go from best to worst.
+    /// A run from the best of them is written with a "+".
+    fn runs(&self, classes: Vec<HandClass>) -> Vec<String> {
+        let mut items = vec![];
+        let mut start = 0;
+        while start < classes.len() {
+            let weight = self.weight(classes[start]);
+            let end = range(start, classes.len()).find(|&index| self.weight(classes[index]) != weight).unwrap_or(classes.len());
+            if weight > 0.0 {
+                let (first, last) = (classes[start].to_notation(), classes[end - 1].to_notation());
+                let item = match (start == 0 && end - start > 1, end - start == 1) {
+                    (true, _) => format!("{}+", last),
+                    (false, true) => first,
+                    (false, false) => format!("{}-{}", first, last),
+                };
+                items.push(match weight < 1.0 {
+                    true => format!("{}:{}", item, weight),
+                    false => item,
+                });
+            }
+            start = end;
+        }
+        items
+    }
+}
+
+impl FromStr for Range {
+    type Err = RangeParseErr;
+    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
+        let mut range = Range::empty();
+        for item in s.split(',').map(|item| item.trim()).filter(|item| !item.is_empty()) {
+            let mut parts = item.split(':');
+            let classes = try!(parse_classes(parts.next().unwrap_or("")));
+            let weight = match parts.next() {
+                Some(weight) => match weight.trim().parse::<f32>() {
+                    Ok(weight) if weight >= 0.0 && weight <= 1.0 => weight,
+                    _ => return Err(RangeParseErr::BadWeight),
+                },
+                None => 1.0,
+            };
+            if parts.next().is_some() {
+                return Err(RangeParseErr::BadWeight);
+            }
+            for class in classes.into_iter() {
+                range.set_weight(class, weight);
+            }
+        }
+        Ok(range)
+    }
+}
+
+/// The classes one item of a range stands for: "QQ", "AKs", "AK" for both suited and offsuit,
+/// "77+" or "A2s+" for everything up to the best of its kind, and "99-66" or "K5s-K2s" for a run.
+fn parse_classes(item: &str) -> Result<Vec<HandClass>, RangeParseErr> {
+    let item = item.trim();
+    if item.ends_with("+") {
+        let (high, low, suitedness) = try!(parse_item(&item[..item.len() - 1]));
+        return Ok(match high == low {
+            true => range(0, low + 1).map(|rank| HandClass::at(rank, rank, false)).collect(),
+            false => range(high + 1, low + 1).flat_map(|rank| with_suitedness(high, rank, suitedness).into_iter()).collect(),
+        });
+    }
+    let mut ends = item.split('-');
+    let first = try!(parse_item(ends.next().unwrap_or("")));
+    match ends.next() {
+        None => Ok(with_suitedness(first.0, first.1, first.2)),
+        Some(last) => {
+            let last = try!(parse_item(last));
+            let pairs = first.0 == first.1 && last.0 == last.1;
+            let same_kind = first.0 == last.0 && first.2 == last.2;
+            if ends.next().is_some() || !(pairs || same_kind) || first.1 > last.1 {
+                return Err(RangeParseErr::BadClass);
+            }
+            Ok(match pairs {
+                true => range(first.0, last.0 + 1).map(|rank| HandClass::at(rank, rank, false)).collect(),
+                false => range(first.1, last.1 + 1).flat_map(|rank| with_suitedness(first.0, rank, first.2).into_iter()).collect(),
+            })
+        },
+    }
+}
+
+/// Chart positions of the two values, highest first, and `Some(suited)` if it was given.
+fn parse_item(item: &str) -> Result<(usize, usize, Option<bool>), RangeParseErr> {
+    let chars = item.trim().chars().collect::<Vec<char>>();
+    let suitedness = match chars.get(2) {
+        None => None,
+        Some(&'s') if chars.len() == 3 => Some(true),
+        Some(&'o') if chars.len() == 3 => Some(false),
+        _ => return Err(RangeParseErr::BadClass),
+    };
+    let positions = chars.iter().take(2)
+                    .filter_map(|&char| Value::from_char(char))
+                    .filter_map(|value| chart_position(&value))
+                    .collect::<Vec<usize>>();
+    if positions.len() != 2 || (positions[0] == positions[1] && suitedness.is_some()) {
+        return Err(RangeParseErr::BadClass);
+    }
+    match positions[0] <= positions[1] {
+        true => Ok((positions[0], positions[1], suitedness)),
+        false => Ok((positions[1], positions[0], suitedness)),
+    }
+}
+
+fn with_suitedness(high: usize, low: usize, suitedness: Option<bool>) -> Vec<HandClass> {
+    match (high == low, suitedness) {
+        (true, _) => vec![HandClass::at(high, low, false)],
+        (false, Some(suited)) => vec![HandClass::at(high, low, suited)],
+        (false, None) => vec![HandClass::at(high, low, true), HandClass::at(high, low, false)],
+    }
+}
+
+/// Aces are at 0 and deuces at 12. Jokers aren't on the chart.
+fn chart_position(value: &Value) -> Option<usize> {
+    match *value {
+        Value::Joker => None,
+        value => Some(12 - value as usize),
+    }
+}
+
+/// Range charts write tens as "T".
+fn value_char(value: &Value) -> char {
+    match *value {
+        Ten => 'T',
+        value => value.to_char(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HandClass, Range, RangeParseErr, TOTAL_COMBOS};
+    use card::{Card, Value, Suit};
+
+    fn parse_range(s: &str) -> Range {
+        s.parse().ok().unwrap()
+    }
+
+    #[test] fn there_are_169_classes_covering_every_combo() {
+        let classes = HandClass::all();
+        assert_eq!(classes.len(), 169);
+        assert!(classes.iter().enumerate().all(|(index, class)| class.index() == index));
+        assert_eq!(classes.iter().fold(0, |combos, class| combos + class.combos().len()), TOTAL_COMBOS);
+    }
+
+    #[test] fn classes_are_found_from_cards() {
+        let class = HandClass::of(&Card::new(Value::Ten, Suit::Heart), &Card::new(Value::Ace, Suit::Heart)).unwrap();
+        assert_eq!(class.to_notation(), "ATs");
+        assert_eq!("ATs".parse::<HandClass>(), Ok(class));
+    }
+
+    #[test] fn jokers_arent_in_any_class() {
+        assert_eq!(HandClass::of(&Card::joker(), &Card::new(Value::Ace, Suit::Heart)), None);
+        assert_eq!("XX".parse::<HandClass>(), Err(RangeParseErr::BadClass));
+        assert_eq!("AX".parse::<HandClass>(), Err(RangeParseErr::BadClass));
+        assert_eq!("AXs".parse::<Range>(), Err(RangeParseErr::BadClass));
+    }
+
+    #[test] fn plus_means_up_to_the_best_of_its_kind() {
+        assert_eq!(parse_range("22+").classes().len(), 13);
+        assert_eq!(parse_range("A2s+").classes().len(), 12);
+        assert_eq!(parse_range("KTo+").combos(), 36.0);
+    }
+
+    #[test] fn dashes_give_a_run() {
+        assert_eq!(parse_range("99-66").classes().len(), 4);
+        assert_eq!(parse_range("K5s-K2s").classes().len(), 4);
+        assert_eq!(parse_range("K5-K2").combos(), 64.0);
+    }
+
+    #[test] fn weights_hold_part_of_a_class() {
+        let range = parse_range("AA, KK:0.5");
+        assert_eq!(range.combos(), 9.0);
+        assert!(range.contains("KK".parse().ok().unwrap()));
+    }
+
+    #[test] fn ranges_round_trip_through_their_notation() {
+        let range = parse_range("TT+, 55-33, AQs+, A5s-A2s, KJo+, 76s:0.5, 72o");
+        assert_eq!(range.to_notation(), "TT+, 55-33, AQs+, A5s-A2s, KJo+, 76s:0.5, 72o");
+        assert_eq!(parse_range(range.to_notation().as_slice()), range);
+    }
+
+    #[test] fn full_range_is_every_hand() {
+        assert_eq!(Range::full().fraction(), 1.0);
+        assert_eq!(parse_range(Range::full().to_notation().as_slice()), Range::full());
+    }
+
+    #[test] fn bad_ranges_are_errors() {
+        assert_eq!("AKx".parse::<Range>(), Err(RangeParseErr::BadClass));
+        assert_eq!("AA:2".parse::<Range>(), Err(RangeParseErr::BadWeight));
+        assert_eq!("AKs-QJs".parse::<Range>(), Err(RangeParseErr::BadClass));
+    }
+}
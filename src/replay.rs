@@ -0,0 +1,241 @@
+use card::Card;
+use hand::Hand;
+use history::{HandHistory, ActionRecord, Blind};
+use calculations::equity_against;
+use table::{Street, Action};
+
+use std::iter::repeat;
+
+/// The hand as it stood at one point of a history.
+#[derive(Debug, Clone)]
+pub struct ReplayState {
+    street: Street,
+    board: Vec<Card>,
+    pot: u32,
+    stacks: Vec<u32>,
+    bets: Vec<u32>,
+    folded: Vec<bool>,
+    to_act: Option<usize>,
+    last_action: Option<ActionRecord>,
+}
+
+impl ReplayState {
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    pub fn board(&self) -> Hand {
+        Hand::new(self.board.clone())
+    }
+
+    /// Everything put in so far, including bets on the current street. Zero once the hand is over and paid out.
+    pub fn pot(&self) -> u32 {
+        self.pot
+    }
+
+    pub fn stacks(&self) -> &Vec<u32> {
+        &self.stacks
+    }
+
+    /// What each seat has put in on the current street.
+    pub fn bets(&self) -> &Vec<u32> {
+        &self.bets
+    }
+
+    pub fn has_folded(&self, seat: usize) -> bool {
+        self.folded[seat]
+    }
+
+    /// The seat whose decision comes next, or `None` once the betting is over.
+    pub fn to_act(&self) -> Option<usize> {
+        self.to_act
+    }
+
+    pub fn to_call(&self) -> u32 {
+        match self.to_act {
+            Some(seat) => {
+                let highest = self.bets.iter().fold(0, |highest, &bet| if bet > highest { bet } else { highest });
+                highest - self.bets[seat]
+            },
+            None => 0,
+        }
+    }
+
+    pub fn last_action(&self) -> Option<ActionRecord> {
+        self.last_action
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.to_act.is_none()
+    }
+}
+
+/// Steps through a hand history. Step 0 is the hand after the blinds and antes, each step after that
+/// is one more action, and the last step is the hand over with the pot paid out.
+pub struct Replay<'a> {
+    history: &'a HandHistory,
+    step: usize,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(history: &'a HandHistory) -> Replay<'a> {
+        Replay { history: history, step: 0 }
+    }
+
+    pub fn steps(&self) -> usize {
+        self.history.actions.len() + 1
+    }
+
+    pub fn state(&self, step: usize) -> ReplayState {
+        let history = self.history;
+        let seats = history.seats.len();
+        let mut stacks = history.seats.iter().map(|seat| seat.stack).collect::<Vec<u32>>();
+        let mut bets = repeat(0).take(seats).collect::<Vec<u32>>();
+        let mut folded = repeat(false).take(seats).collect::<Vec<bool>>();
+        let mut pot = 0;
+        for post in history.posts.iter() {
+            stacks[post.seat] -= post.amount;
+            pot += post.amount;
+            if post.blind != Blind::Ante {
+                bets[post.seat] += post.amount;
+            }
+        }
+        let mut street = Street::Preflop;
+        for action in history.actions.iter().take(step) {
+            if action.street != street {
+                street = action.street;
+                bets = repeat(0).take(seats).collect();
+            }
+            stacks[action.seat] -= action.amount;
+            bets[action.seat] += action.amount;
+            pot += action.amount;
+            if action.action == Action::Fold {
+                folded[action.seat] = true;
+            }
+        }
+
+        let last_action = match step {
+            0 => None,
+            _ => history.actions.get(step - 1).map(|&action| action),
+        };
+        match history.actions.get(step) {
+            Some(next) => {
+                if next.street != street {
+                    bets = repeat(0).take(seats).collect();
+                }
+                ReplayState {
+                    street: next.street,
+                    board: history.board_on(next.street).cards(),
+                    pot: pot,
+                    stacks: stacks,
+                    bets: bets,
+                    folded: folded,
+                    to_act: Some(next.seat),
+                    last_action: last_action,
+                }
+            },
+            None => ReplayState {
+                street: match history.showdown.is_empty() {
+                    true => street,
+                    false => Street::Showdown,
+                },
+                board: history.board.clone(),
+                pot: 0,
+                stacks: stacks.iter().zip(history.winnings.iter()).map(|(&stack, &won)| stack + won).collect(),
+                bets: repeat(0).take(seats).collect(),
+                folded: folded,
+                to_act: None,
+                last_action: last_action,
+            },
+        }
+    }
+
+    /// `hero`'s share of the pot at `step` against every opponent still in the hand, with the rest of
+    /// the board to come. Opponents hold `assumed` cards where given, the cards they were seen with where
+    /// not, and random cards otherwise. `None` if the hero's cards aren't known or they've folded.
+    pub fn equity(&self, step: usize, hero: usize, assumed: &Vec<Option<Hand>>) -> Option<(f32, f32)> {
+        let state = self.state(step);
+        let hero_cards = match self.history.hole_cards[hero] {
+            Some(ref cards) if !state.has_folded(hero) => cards.clone(),
+            _ => return None,
+        };
+        let opponents = range(0, self.history.seats.len())
+                        .filter(|&seat| seat != hero && !state.has_folded(seat))
+                        .map(|seat| match assumed.get(seat) {
+                            Some(&Some(ref cards)) => Some(cards.clone()),
+                            _ => self.history.hole_cards[seat].clone(),
+                        })
+                        .collect::<Vec<Option<Hand>>>();
+        let known = opponents.iter().filter_map(|cards| cards.clone()).collect::<Vec<Hand>>();
+        let unknown = opponents.len() - known.len();
+        Some(equity_against(hero_cards, &known, state.board(), Hand::new(vec![]), unknown))
+    }
+}
+
+impl<'a> Iterator for Replay<'a> {
+    type Item = ReplayState;
+
+    fn next(&mut self) -> Option<ReplayState> {
+        match self.step < self.steps() {
+            true => {
+                self.step += 1;
+                Some(self.state(self.step - 1))
+            },
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Replay;
+    use hand::Hand;
+    use table::{Street, played_hand};
+
+    #[test] fn the_first_step_is_after_the_blinds() {
+        let history = played_hand().history();
+        let state = Replay::new(&history).state(0);
+        assert_eq!(state.pot(), 3);
+        assert_eq!(state.stacks(), &vec![99, 98]);
+        assert_eq!(state.to_act(), Some(0));
+        assert_eq!(state.to_call(), 1);
+        assert!(state.last_action().is_none());
+    }
+
+    #[test] fn each_step_is_one_more_action() {
+        let history = played_hand().history();
+        let state = Replay::new(&history).state(2);
+        assert_eq!(state.street(), Street::Flop);
+        assert_eq!(state.board().cards().len(), 3);
+        assert_eq!(state.pot(), 12);
+        assert_eq!(state.bets(), &vec![0, 0]);
+        assert_eq!(state.to_act(), Some(1));
+    }
+
+    #[test] fn the_last_step_pays_out_the_pot() {
+        let history = played_hand().history();
+        let states = Replay::new(&history).collect::<Vec<_>>();
+        assert_eq!(states.len(), 9);
+        let last = states.last().unwrap();
+        assert!(last.is_over());
+        assert_eq!(last.street(), Street::Showdown);
+        assert_eq!(last.pot(), 0);
+        assert_eq!(last.stacks(), &vec![84, 116]);
+    }
+
+    #[test] fn equity_uses_the_revealed_hands() {
+        let history = played_hand().history();
+        let replay = Replay::new(&history);
+        // Aces against kings, all in on the river in all but name.
+        assert_eq!(replay.equity(7, 1, &vec![]), Some((1.0, 0.0)));
+        assert_eq!(replay.equity(7, 0, &vec![]), Some((0.0, 0.0)));
+    }
+
+    #[test] fn assumed_hands_replace_the_revealed_ones() {
+        let history = played_hand().history();
+        let replay = Replay::new(&history);
+        // The kings actually lost, but a ten makes a straight on this board.
+        let assumed = vec![Some("0S 2S".parse::<Hand>().ok().unwrap()), None];
+        assert_eq!(replay.equity(7, 1, &assumed), Some((0.0, 0.0)));
+    }
+}
@@ -0,0 +1,350 @@
+use history::{HandHistory, Blind};
+use table::{Street, Action};
+
+use std::collections::HashMap;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Position {
+    SmallBlind,
+    BigBlind,
+    Early,
+    Middle,
+    Cutoff,
+    Button,
+}
+
+/// Where `seat` sat relative to the button, going by the blinds posted where there are any.
+/// Only the seats dealt in count, along with the button.
+/// Heads-up, the button posting the small blind counts as the button.
+/// Of the seats between the big blind and the cutoff, the first half are early and the rest middle.
+pub fn position(history: &HandHistory, seat: usize) -> Position {
+    let posted = |blind: Blind| history.posts.iter().any(|post| post.seat == seat && post.blind == blind);
+    if seat == history.button {
+        return Position::Button;
+    }
+    if posted(Blind::SmallBlind) {
+        return Position::SmallBlind;
+    }
+    if posted(Blind::BigBlind) {
+        return Position::BigBlind;
+    }
+    // Going round from the seat after the button and ending with the button itself.
+    let playing = range(1, history.seats.len() + 1)
+                  .map(|offset| (history.button + offset) % history.seats.len())
+                  .filter(|&other| other == seat || other == history.button || dealt_in(history, other))
+                  .collect::<Vec<usize>>();
+    let seats = playing.len();
+    let after_button = playing.iter().position(|&other| other == seat).unwrap() + 1;
+    match (after_button, seats - after_button) {
+        (_, 1) => Position::Cutoff,
+        (1, _) => Position::SmallBlind,
+        (2, _) => Position::BigBlind,
+        _ => {
+            // The seats between the big blind and the cutoff.
+            let open_seats = seats - 4;
+            match after_button - 3 < open_seats / 2 {
+                true => Position::Early,
+                false => Position::Middle,
+            }
+        },
+    }
+}
+
+/// Which hands to count: only those played from `positions`, if given, and with a starting stack
+/// between `min_depth` and `max_depth` big blinds.
+#[derive(Debug, Clone)]
+pub struct StatsFilter {
+    positions: Option<Vec<Position>>,
+    min_depth: u32,
+    max_depth: u32,
+}
+
+impl StatsFilter {
+    pub fn all() -> StatsFilter {
+        StatsFilter { positions: None, min_depth: 0, max_depth: !0 }
+    }
+
+    pub fn with_positions(mut self, positions: Vec<Position>) -> StatsFilter {
+        self.positions = Some(positions);
+        self
+    }
+
+    pub fn with_depth(mut self, min_depth: u32, max_depth: u32) -> StatsFilter {
+        self.min_depth = min_depth;
+        self.max_depth = max_depth;
+        self
+    }
+
+    fn includes(&self, history: &HandHistory, seat: usize) -> bool {
+        let depth = match history.big_blind {
+            0 => 0,
+            big_blind => history.seats[seat].stack / big_blind,
+        };
+        depth >= self.min_depth && depth <= self.max_depth && match self.positions {
+            Some(ref positions) => positions.contains(&position(history, seat)),
+            None => true,
+        }
+    }
+}
+
+/// One player's tendencies over a set of hands. Each rate is `None` until the player has had a chance at it.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct PlayerStats {
+    hands: usize,
+    voluntarily_put_in: usize,
+    preflop_raises: usize,
+    three_bet_chances: usize,
+    three_bets: usize,
+    cbets_faced: usize,
+    folds_to_cbet: usize,
+    aggressive_actions: usize,
+    calls: usize,
+    saw_flop: usize,
+    showdowns: usize,
+    showdowns_won: usize,
+}
+
+impl PlayerStats {
+    pub fn new() -> PlayerStats {
+        PlayerStats {
+            hands: 0,
+            voluntarily_put_in: 0,
+            preflop_raises: 0,
+            three_bet_chances: 0,
+            three_bets: 0,
+            cbets_faced: 0,
+            folds_to_cbet: 0,
+            aggressive_actions: 0,
+            calls: 0,
+            saw_flop: 0,
+            showdowns: 0,
+            showdowns_won: 0,
+        }
+    }
+
+    pub fn hands(&self) -> usize {
+        self.hands
+    }
+
+    /// How often the player put money in preflop when they didn't have to.
+    pub fn vpip(&self) -> Option<f32> {
+        ratio(self.voluntarily_put_in, self.hands)
+    }
+
+    pub fn pfr(&self) -> Option<f32> {
+        ratio(self.preflop_raises, self.hands)
+    }
+
+    /// How often the player reraised when facing a single preflop raise.
+    pub fn three_bet(&self) -> Option<f32> {
+        ratio(self.three_bets, self.three_bet_chances)
+    }
+
+    /// How often the player folded to a flop bet from the preflop raiser.
+    pub fn fold_to_cbet(&self) -> Option<f32> {
+        ratio(self.folds_to_cbet, self.cbets_faced)
+    }
+
+    /// Bets and raises for every call after the flop.
+    pub fn aggression_factor(&self) -> Option<f32> {
+        ratio(self.aggressive_actions, self.calls)
+    }
+
+    pub fn went_to_showdown(&self) -> Option<f32> {
+        ratio(self.showdowns, self.saw_flop)
+    }
+
+    pub fn won_at_showdown(&self) -> Option<f32> {
+        ratio(self.showdowns_won, self.showdowns)
+    }
+
+    fn add_hand(&mut self, history: &HandHistory, seat: usize) {
+        self.hands += 1;
+
+        let mut raises = 0;
+        let mut aggressor = None;
+        let (mut put_in, mut raised, mut three_bet_chance, mut three_bet) = (false, false, false, false);
+        for action in history.actions.iter().filter(|action| action.street == Street::Preflop) {
+            if action.seat == seat {
+                if raises == 1 {
+                    three_bet_chance = true;
+                }
+                match action.action {
+                    Action::Call => put_in = true,
+                    Action::Bet(_) | Action::Raise(_) => {
+                        put_in = true;
+                        raised = true;
+                        three_bet = three_bet || raises == 1;
+                    },
+                    _ => (),
+                }
+            }
+            if is_aggressive(action.action) {
+                raises += 1;
+                aggressor = Some(action.seat);
+            }
+        }
+        self.voluntarily_put_in += put_in as usize;
+        self.preflop_raises += raised as usize;
+        self.three_bet_chances += three_bet_chance as usize;
+        self.three_bets += three_bet as usize;
+
+        // The preflop raiser's flop bet, if they led the betting there, and everyone's answer to it.
+        let flop = history.actions.iter().filter(|action| action.street == Street::Flop).collect::<Vec<_>>();
+        match flop.iter().position(|action| is_aggressive(action.action)) {
+            Some(cbet) if aggressor == Some(flop[cbet].seat) && aggressor != Some(seat) => {
+                let answer = flop.iter()
+                                 .skip(cbet + 1)
+                                 .take_while(|action| !is_aggressive(action.action) || action.seat == seat)
+                                 .find(|action| action.seat == seat);
+                if let Some(answer) = answer {
+                    self.cbets_faced += 1;
+                    self.folds_to_cbet += (answer.action == Action::Fold) as usize;
+                }
+            },
+            _ => (),
+        }
+
+        for action in history.actions.iter().filter(|action| action.seat == seat && action.street != Street::Preflop) {
+            match action.action {
+                Action::Call => self.calls += 1,
+                action if is_aggressive(action) => self.aggressive_actions += 1,
+                _ => (),
+            }
+        }
+
+        let folded = |seat: usize| history.actions.iter().any(|action| action.seat == seat && action.action == Action::Fold);
+        let folded_preflop = history.actions.iter()
+                                    .any(|action| action.seat == seat && action.street == Street::Preflop && action.action == Action::Fold);
+        if !folded_preflop && history.board.len() >= 3 {
+            self.saw_flop += 1;
+            let live = range(0, history.seats.len()).filter(|&other| dealt_in(history, other) && !folded(other)).count();
+            if !folded(seat) && live >= 2 {
+                self.showdowns += 1;
+                self.showdowns_won += (history.winnings[seat] > 0) as usize;
+            }
+        }
+    }
+}
+
+/// Every player's stats over `histories`, by name, counting only the hands `filter` lets through.
+pub fn player_stats(histories: &Vec<HandHistory>, filter: &StatsFilter) -> HashMap<String, PlayerStats> {
+    let mut stats = HashMap::new();
+    for history in histories.iter() {
+        for seat in range(0, history.seats.len()).filter(|&seat| dealt_in(history, seat) && filter.includes(history, seat)) {
+            let name = history.seats[seat].name.clone();
+            if !stats.contains_key(&name) {
+                stats.insert(name.clone(), PlayerStats::new());
+            }
+            stats.get_mut(&name).unwrap().add_hand(history, seat);
+        }
+    }
+    stats
+}
+
+/// Seats sitting out neither post nor act.
+fn dealt_in(history: &HandHistory, seat: usize) -> bool {
+    history.posts.iter().any(|post| post.seat == seat) || history.actions.iter().any(|action| action.seat == seat)
+}
+
+fn is_aggressive(action: Action) -> bool {
+    match action {
+        Action::Bet(_) | Action::Raise(_) => true,
+        _ => false,
+    }
+}
+
+fn ratio(count: usize, chances: usize) -> Option<f32> {
+    match chances {
+        0 => None,
+        _ => Some(count as f32 / chances as f32),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{player_stats, position, Position, StatsFilter};
+    use history::HandHistory;
+    use table::{Table, HandState, Action, stacked_deck, played_hand};
+
+    fn play(stacks: Vec<u32>, deck: &str, actions: Vec<Action>) -> HandHistory {
+        let mut hand = HandState::new(&Table::new(stacks, 1, 2), stacked_deck(deck)).ok().unwrap();
+        for action in actions.into_iter() {
+            hand.act(action).ok().unwrap();
+        }
+        hand.history()
+    }
+
+    /// Seat 1 opens, seat 3 three-bets and seat 1 calls, then folds to seat 3's continuation bet.
+    fn three_bet_pot() -> HandHistory {
+        play(vec![100, 100, 100], "2C 3D 4H 5S 6C 7D",
+             vec![Action::Raise(6), Action::Fold, Action::Raise(18), Action::Call, Action::Bet(20), Action::Fold])
+    }
+
+    /// Kings raise, aces call and bet the flop, and the hand checks down to a showdown the aces win.
+    fn showdown() -> HandHistory {
+        played_hand().history()
+    }
+
+    #[test] fn preflop_stats_count_voluntary_chips_and_raises() {
+        let stats = player_stats(&vec![three_bet_pot()], &StatsFilter::all());
+        assert_eq!(stats.get(&"Seat 1".to_string()).unwrap().vpip(), Some(1.0));
+        assert_eq!(stats.get(&"Seat 1".to_string()).unwrap().pfr(), Some(1.0));
+        assert_eq!(stats.get(&"Seat 2".to_string()).unwrap().vpip(), Some(0.0));
+    }
+
+    #[test] fn three_bets_need_a_single_raise_to_face() {
+        let stats = player_stats(&vec![three_bet_pot()], &StatsFilter::all());
+        assert_eq!(stats.get(&"Seat 1".to_string()).unwrap().three_bet(), None);
+        assert_eq!(stats.get(&"Seat 2".to_string()).unwrap().three_bet(), Some(0.0));
+        assert_eq!(stats.get(&"Seat 3".to_string()).unwrap().three_bet(), Some(1.0));
+    }
+
+    #[test] fn folding_to_the_preflop_raisers_flop_bet() {
+        let stats = player_stats(&vec![three_bet_pot()], &StatsFilter::all());
+        assert_eq!(stats.get(&"Seat 1".to_string()).unwrap().fold_to_cbet(), Some(1.0));
+        assert_eq!(stats.get(&"Seat 3".to_string()).unwrap().fold_to_cbet(), None);
+    }
+
+    #[test] fn leading_into_the_raiser_is_not_a_continuation_bet() {
+        let stats = player_stats(&vec![showdown()], &StatsFilter::all());
+        assert_eq!(stats.get(&"Seat 1".to_string()).unwrap().fold_to_cbet(), None);
+    }
+
+    #[test] fn aggression_counts_bets_and_raises_against_calls_after_the_flop() {
+        let stats = player_stats(&vec![showdown()], &StatsFilter::all());
+        assert_eq!(stats.get(&"Seat 1".to_string()).unwrap().aggression_factor(), Some(0.0));
+        assert_eq!(stats.get(&"Seat 2".to_string()).unwrap().aggression_factor(), None);
+    }
+
+    #[test] fn showdowns_are_counted_from_the_flop() {
+        let stats = player_stats(&vec![showdown(), three_bet_pot()], &StatsFilter::all());
+        assert_eq!(stats.get(&"Seat 1".to_string()).unwrap().hands(), 2);
+        assert_eq!(stats.get(&"Seat 1".to_string()).unwrap().went_to_showdown(), Some(0.5));
+        assert_eq!(stats.get(&"Seat 1".to_string()).unwrap().won_at_showdown(), Some(0.0));
+        assert_eq!(stats.get(&"Seat 2".to_string()).unwrap().went_to_showdown(), Some(1.0));
+        assert_eq!(stats.get(&"Seat 2".to_string()).unwrap().won_at_showdown(), Some(1.0));
+    }
+
+    #[test] fn positions_go_round_from_the_button() {
+        let history = play(vec![100, 100, 100, 100, 100, 100], "2C 3D", vec![]);
+        let positions = range(0, 6).map(|seat| position(&history, seat)).collect::<Vec<Position>>();
+        assert_eq!(positions, vec![Position::Button, Position::SmallBlind, Position::BigBlind,
+                                   Position::Early, Position::Middle, Position::Cutoff]);
+    }
+
+    #[test] fn seats_sitting_out_do_not_count_towards_positions() {
+        let history = play(vec![100, 100, 0, 100, 100, 100, 100], "2C 3D", vec![]);
+        let positions = vec![0, 1, 3, 4, 5, 6].into_iter().map(|seat| position(&history, seat)).collect::<Vec<Position>>();
+        assert_eq!(positions, vec![Position::Button, Position::SmallBlind, Position::BigBlind,
+                                   Position::Early, Position::Middle, Position::Cutoff]);
+    }
+
+    #[test] fn hands_can_be_filtered_by_position_and_depth() {
+        let histories = vec![three_bet_pot()];
+        let button_only = player_stats(&histories, &StatsFilter::all().with_positions(vec![Position::Button]));
+        assert_eq!(button_only.keys().collect::<Vec<&String>>(), vec![&"Seat 1".to_string()]);
+        assert!(player_stats(&histories, &StatsFilter::all().with_depth(0, 40)).is_empty());
+        assert_eq!(player_stats(&histories, &StatsFilter::all().with_depth(40, 60)).len(), 3);
+    }
+}
@@ -0,0 +1,273 @@
+use card::{Card, Value};
+use hand::Hand;
+use low::{LowHand, ace_low_rank};
+use calculations::{whole_deck, confidence_interval, SAMPLES};
+
+use std::rand::{thread_rng, Rng};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum StudErr {
+    DeckEmpty,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum StudGame {
+    Stud,
+    StudHiLo,
+    Razz,
+}
+
+/// A seven-card stud player. The first two cards and the seventh are dealt down, the rest up.
+#[derive(Debug, Clone)]
+pub struct StudPlayer {
+    down_cards: Vec<Card>,
+    up_cards: Vec<Card>,
+    folded: bool,
+}
+
+impl StudPlayer {
+    pub fn new() -> StudPlayer {
+        StudPlayer { down_cards: vec![], up_cards: vec![], folded: false }
+    }
+
+    pub fn deal(&mut self, card: Card) {
+        match next_card_is_up(self.cards_dealt()) {
+            true => self.up_cards.push(card),
+            false => self.down_cards.push(card),
+        }
+    }
+
+    /// The card everyone shares when the deck runs out on seventh street. It plays face up.
+    pub fn deal_community(&mut self, card: Card) {
+        self.up_cards.push(card);
+    }
+
+    pub fn fold(&mut self) {
+        self.folded = true;
+    }
+
+    pub fn has_folded(&self) -> bool {
+        self.folded
+    }
+
+    pub fn cards_dealt(&self) -> usize {
+        self.down_cards.len() + self.up_cards.len()
+    }
+
+    pub fn up_cards(&self) -> Hand {
+        Hand::new(self.up_cards.clone())
+    }
+
+    pub fn cards(&self) -> Hand {
+        Hand::new(self.down_cards.clone()) + self.up_cards()
+    }
+}
+
+pub fn next_card_is_up(cards_dealt: usize) -> bool {
+    match cards_dealt {
+        2...5 => true,
+        _ => false,
+    }
+}
+
+/// Deals the next street to every player still in the hand. When there aren't enough cards left for
+/// everyone, as with eight players on seventh street, a single card is dealt face up for them all to share.
+pub fn deal_street(players: &mut Vec<StudPlayer>, deck: &mut Vec<Card>) -> Result<(), StudErr> {
+    let in_hand = players.iter().filter(|player| !player.has_folded()).count();
+    if deck.len() < in_hand {
+        let card = match deck.pop() {
+            Some(card) => card,
+            None => return Err(StudErr::DeckEmpty),
+        };
+        for player in players.iter_mut().filter(|player| !player.has_folded()) {
+            player.deal_community(card);
+        }
+        return Ok(());
+    }
+    for player in players.iter_mut().filter(|player| !player.has_folded()) {
+        player.deal(deck.pop().unwrap());
+    }
+    Ok(())
+}
+
+/// The player forced to bring it in on third street: the lowest door card in stud and stud hi-lo,
+/// the highest in razz. Ties go by suit.
+pub fn bring_in(game: StudGame, players: &Vec<StudPlayer>) -> Option<usize> {
+    let door_cards = players.iter()
+                            .enumerate()
+                            .filter_map(|(seat, player)| player.up_cards.get(0).map(|card| (seat, *card)));
+    match game {
+        StudGame::Razz => door_cards.max_by(|&(_, card)| (ace_low_rank(card.value()), *card.suit())),
+        _ => door_cards.min_by(|&(_, card)| (*card.value(), *card.suit())),
+    }.map(|(seat, _)| seat)
+}
+
+/// The player who acts first from fourth street on: the best showing hand, or the best showing low in razz.
+/// Ties go to the earliest seat.
+pub fn first_to_act(game: StudGame, players: &Vec<StudPlayer>) -> Option<usize> {
+    let showing = players.iter().enumerate().filter(|&(_, player)| !player.has_folded());
+    match game {
+        StudGame::Razz => first_best(showing.map(|(seat, player)| (seat, LowHand::new(&player.up_cards)))),
+        _ => first_best(showing.map(|(seat, player)| (seat, player.up_cards()))),
+    }
+}
+
+fn first_best<T: Ord, I: Iterator<Item=(usize, T)>>(showing: I) -> Option<usize> {
+    showing.fold(None, |best: Option<(usize, T)>, (seat, hand)| {
+        match best {
+            Some((best_seat, best_hand)) => match hand > best_hand {
+                true => Some((seat, hand)),
+                false => Some((best_seat, best_hand)),
+            },
+            None => Some((seat, hand)),
+        }
+    }).map(|(seat, _)| seat)
+}
+
+/// The hero's share of the pot, running the rest of the hand out at random.
+/// Everyone's up cards, and any `dead_cards` such as folded players' exposed cards, can't be dealt.
+pub fn chance_of_winning(game: StudGame, hero: usize, players: &Vec<StudPlayer>, dead_cards: Hand) -> (f32, f32) {
+    let mut rng = thread_rng();
+    let known = players.iter().fold(players[hero].cards() + dead_cards, |known, player| known + player.up_cards());
+    let remaining_deck = whole_deck() - known;
+    let shares = range(0, SAMPLES)
+                 .map(|_| {
+                     let mut deck_cards = remaining_deck.cards();
+                     rng.shuffle(deck_cards.as_mut_slice());
+                     let mut deck_iter = deck_cards.into_iter();
+                     let hands = players.iter().enumerate()
+                         .map(|(seat, player)| {
+                             match (player.has_folded(), seat == hero) {
+                                 (true, _) => None,
+                                 (false, true) => {
+                                     let needed = 7 - player.cards_dealt();
+                                     Some(player.cards() + Hand::new(deck_iter.by_ref().take(needed).collect()))
+                                 },
+                                 (false, false) => {
+                                     let needed = 7 - player.up_cards.len();
+                                     Some(player.up_cards() + Hand::new(deck_iter.by_ref().take(needed).collect()))
+                                 },
+                             }
+                         }).collect::<Vec<Option<Hand>>>();
+                     pot_share(game, hero, &hands)
+                 }).collect::<Vec<f32>>();
+    let total: f32 = shares.iter().fold(0.0, |sum, _| sum + 1.0);
+    let won: f32 = shares.iter().fold(0.0, |sum, share| sum + *share);
+    (won / total, confidence_interval(won, total))
+}
+
+fn pot_share(game: StudGame, hero: usize, hands: &Vec<Option<Hand>>) -> f32 {
+    let lows = |qualifier: Option<Value>| {
+        hands.iter().map(|hand| {
+            hand.as_ref().and_then(|hand| match qualifier {
+                Some(qualifier) => LowHand::best_qualifying(&hand.cards(), qualifier),
+                None => LowHand::best(&hand.cards()),
+            })
+        }).collect::<Vec<Option<LowHand>>>()
+    };
+    match game {
+        StudGame::Stud => share_of(hero, hands),
+        StudGame::Razz => share_of(hero, &lows(None)),
+        StudGame::StudHiLo => {
+            let qualified_lows = lows(Some(Value::Eight));
+            match qualified_lows.iter().all(|low| low.is_none()) {
+                true => share_of(hero, hands),
+                false => (share_of(hero, hands) + share_of(hero, &qualified_lows)) / 2.0,
+            }
+        },
+    }
+}
+
+fn share_of<T: Ord>(hero: usize, hands: &Vec<Option<T>>) -> f32 {
+    let best = match hands.iter().filter_map(|hand| hand.as_ref()).max() {
+        Some(best) => best,
+        None => return 0.0,
+    };
+    let tied = hands.iter().filter_map(|hand| hand.as_ref()).filter(|&hand| hand == best).count();
+    match hands[hero] {
+        Some(ref hand) if hand == best => 1.0 / tied as f32,
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StudPlayer, StudGame, StudErr, bring_in, first_to_act, next_card_is_up, deal_street};
+    use card::Card;
+    use calculations::whole_deck;
+
+    fn player(cards: &str) -> StudPlayer {
+        let mut player = StudPlayer::new();
+        for card in cards.split_str(" ") {
+            player.deal(card.parse::<Card>().ok().unwrap());
+        }
+        player
+    }
+
+    #[test] fn third_and_seventh_street_deal_down_and_up() {
+        let dealt = range(0, 7).map(|cards_dealt| next_card_is_up(cards_dealt)).collect::<Vec<bool>>();
+        assert_eq!(dealt, vec![false, false, true, true, true, true, false]);
+    }
+
+    #[test] fn lowest_door_card_brings_in() {
+        let players = vec![player("AS AH 5C"), player("2S 2H 3D"), player("KD KC 9H")];
+        assert_eq!(bring_in(StudGame::Stud, &players), Some(1));
+    }
+
+    #[test] fn bring_in_ties_are_broken_by_suit() {
+        let players = vec![player("AS AH 3S"), player("2S 2H 3D"), player("KD KC 3H")];
+        assert_eq!(bring_in(StudGame::Stud, &players), Some(1));
+    }
+
+    #[test] fn ace_is_high_for_the_stud_bring_in() {
+        let players = vec![player("2S 2H AC"), player("AS AH 2D")];
+        assert_eq!(bring_in(StudGame::StudHiLo, &players), Some(1));
+    }
+
+    #[test] fn highest_door_card_brings_in_at_razz() {
+        let players = vec![player("AS AH KC"), player("2S 2H AD"), player("KD KS KH")];
+        assert_eq!(bring_in(StudGame::Razz, &players), Some(2));
+    }
+
+    #[test] fn best_showing_hand_acts_first() {
+        let players = vec![player("AS AH 5C 6D"), player("2S 2H 3D 3C"), player("KD KC AH QH")];
+        assert_eq!(first_to_act(StudGame::Stud, &players), Some(1));
+    }
+
+    #[test] fn tied_showing_hands_act_in_seat_order() {
+        let players = vec![player("AS AH 5C 6D"), player("2S 2H 5D 6C")];
+        assert_eq!(first_to_act(StudGame::Stud, &players), Some(0));
+    }
+
+    #[test] fn folded_players_do_not_act_first() {
+        let mut players = vec![player("2S 2H 3D 3C"), player("AS AH 5C 6D")];
+        players[0].fold();
+        assert_eq!(first_to_act(StudGame::Stud, &players), Some(1));
+    }
+
+    #[test] fn best_showing_low_acts_first_at_razz() {
+        let players = vec![player("AS AH 5C 6D"), player("2S 2H 3D 3C"), player("KD KC AH 2H")];
+        assert_eq!(first_to_act(StudGame::Razz, &players), Some(2));
+    }
+
+    #[test] fn eight_players_share_a_card_on_seventh_street() {
+        let mut deck = whole_deck().cards();
+        let mut players = range(0, 8).map(|_| StudPlayer::new()).collect::<Vec<StudPlayer>>();
+        for _ in range(0, 6) {
+            deal_street(&mut players, &mut deck).ok().unwrap();
+        }
+        assert_eq!(deck.len(), 4);
+        let community = deck[3];
+        deal_street(&mut players, &mut deck).ok().unwrap();
+        assert_eq!(deck.len(), 3);
+        for player in players.iter() {
+            assert_eq!(player.cards().cards().len(), 7);
+            assert!(player.up_cards().cards().contains(&community));
+        }
+    }
+
+    #[test] fn streets_need_a_card_to_deal() {
+        let mut players = vec![StudPlayer::new(), StudPlayer::new()];
+        assert_eq!(deal_street(&mut players, &mut vec![]), Err(StudErr::DeckEmpty));
+    }
+}
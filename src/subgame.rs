@@ -0,0 +1,342 @@
+use card::Card;
+use hand::Hand;
+use range::Range;
+use cfr::{self, Game, Node, Solver, Strategy, Variant};
+
+use std::cmp::{max, Ordering};
+use std::num::Float;
+
+/// A move on the river. Bets and raises say what the player's whole bet comes to, not what it adds.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum RiverAction {
+    Check,
+    Fold,
+    Call,
+    Bet(u32),
+}
+
+impl RiverAction {
+    fn notation(&self) -> String {
+        match *self {
+            RiverAction::Check => "k".to_string(),
+            RiverAction::Fold => "f".to_string(),
+            RiverAction::Call => "c".to_string(),
+            RiverAction::Bet(to) => format!("b{}", to),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum RiverSpotErr {
+    /// The board and the ranges leave no pair of combos the players could hold together.
+    NoDeals,
+}
+
+/// A heads-up no-limit river: two ranges on a finished board, a pot, and the smaller of the two stacks
+/// left behind. Bets are sized as fractions of the pot; raises as fractions of the pot once the bet's
+/// called. Player 0 is out of position and acts first.
+#[derive(Debug, Clone)]
+pub struct RiverSpot {
+    combos: Vec<Vec<(Card, Card)>>,
+    deals: Vec<(usize, usize, f64)>,
+    showdowns: Vec<Ordering>,
+    pot: u32,
+    stack: u32,
+    bet_sizes: Vec<f32>,
+    max_raises: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct RiverSpotState {
+    deal: Option<usize>,
+    line: Vec<RiverAction>,
+}
+
+impl RiverSpot {
+    /// Combos that share a card with the board can't be held, and each pair of combos the two players
+    /// can hold together is dealt as often as their weights in the ranges say.
+    pub fn new(board: Hand, first: &Range, second: &Range, pot: u32, stack: u32) -> Result<RiverSpot, RiverSpotErr> {
+        let board_cards = board.cards();
+        let weighted = [first, second].iter()
+                       .map(|held| {
+                           held.classes().into_iter()
+                               .flat_map(|class| class.combos().into_iter().map(move |combo| (combo, held.weight(class))))
+                               .filter(|&((a, b), _)| !board_cards.contains(&a) && !board_cards.contains(&b))
+                               .collect::<Vec<((Card, Card), f32)>>()
+                       })
+                       .collect::<Vec<_>>();
+        let mut deals = vec![];
+        let mut showdowns = vec![];
+        for (first_index, &((a, b), first_weight)) in weighted[0].iter().enumerate() {
+            for (second_index, &((c, d), second_weight)) in weighted[1].iter().enumerate() {
+                if a == c || a == d || b == c || b == d {
+                    continue;
+                }
+                deals.push((first_index, second_index, first_weight as f64 * second_weight as f64));
+                let first_hand = Hand::new(vec![a, b]) + board.clone();
+                let second_hand = Hand::new(vec![c, d]) + board.clone();
+                showdowns.push(first_hand.cmp(&second_hand));
+            }
+        }
+        let total = deals.iter().fold(0.0, |total, &(_, _, weight)| total + weight);
+        if total <= 0.0 {
+            return Err(RiverSpotErr::NoDeals);
+        }
+        Ok(RiverSpot {
+            combos: weighted.iter().map(|combos| combos.iter().map(|&(combo, _)| combo).collect()).collect(),
+            deals: deals.into_iter().map(|(first, second, weight)| (first, second, weight / total)).collect(),
+            showdowns: showdowns,
+            pot: pot,
+            stack: stack,
+            bet_sizes: vec![0.5, 1.0],
+            max_raises: 2,
+        })
+    }
+
+    /// Bet sizes as fractions of the pot, half and full pot by default. Sizes bigger than the stack go all in,
+    /// and raises too small to be legal go up to the minimum raise.
+    pub fn with_bet_sizes(mut self, bet_sizes: Vec<f32>) -> RiverSpot {
+        self.bet_sizes = bet_sizes;
+        self
+    }
+
+    /// How many raises are allowed after the first bet, two by default.
+    pub fn with_max_raises(mut self, max_raises: usize) -> RiverSpot {
+        self.max_raises = max_raises;
+        self
+    }
+
+    pub fn combos(&self, player: usize) -> &Vec<(Card, Card)> {
+        &self.combos[player]
+    }
+
+    /// What the player to act can do after `line`.
+    pub fn legal_actions(&self, line: &Vec<RiverAction>) -> Vec<RiverAction> {
+        let player = line.len() % 2;
+        let put_in = put_in(line);
+        let highest = if put_in[0] > put_in[1] { put_in[0] } else { put_in[1] };
+        let bets = line.iter().filter(|&&action| match action { RiverAction::Bet(_) => true, _ => false }).count();
+        let (mut actions, sizes) = match highest > put_in[player] {
+            true if highest >= self.stack || bets > self.max_raises => (vec![RiverAction::Fold, RiverAction::Call], vec![]),
+            true => {
+                let pot_after_call = (self.pot + 2 * highest) as f32;
+                // A raise has to go up by at least as much as the bet it raises.
+                let min_raise = highest + (highest - put_in[player]);
+                (vec![RiverAction::Fold, RiverAction::Call],
+                 self.bet_sizes.iter().map(|size| max(highest + (size * pot_after_call).round() as u32, min_raise)).collect())
+            },
+            false => (vec![RiverAction::Check], self.bet_sizes.iter().map(|size| (size * self.pot as f32).round() as u32).collect::<Vec<u32>>()),
+        };
+        for size in sizes.into_iter() {
+            let bet = RiverAction::Bet(if size > self.stack { self.stack } else if size == 0 { 1 } else { size });
+            if !actions.contains(&bet) {
+                actions.push(bet);
+            }
+        }
+        actions
+    }
+
+    fn information_set_of(&self, player: usize, combo: usize, line: &Vec<RiverAction>) -> String {
+        let (a, b) = self.combos[player][combo];
+        let line = line.iter().map(|action| action.notation()).collect::<Vec<String>>();
+        format!("{}{}{}{}:{}", a.value().to_char(), a.suit().to_char(), b.value().to_char(), b.suit().to_char(), line.connect(","))
+    }
+}
+
+/// What each player has put in on the river.
+fn put_in(line: &Vec<RiverAction>) -> [u32; 2] {
+    let mut put_in = [0, 0];
+    for (turn, action) in line.iter().enumerate() {
+        let highest = if put_in[0] > put_in[1] { put_in[0] } else { put_in[1] };
+        match *action {
+            RiverAction::Bet(to) => put_in[turn % 2] = to,
+            RiverAction::Call => put_in[turn % 2] = highest,
+            _ => (),
+        }
+    }
+    put_in
+}
+
+impl Game for RiverSpot {
+    type State = RiverSpotState;
+    type Action = RiverAction;
+
+    fn root(&self) -> RiverSpotState {
+        RiverSpotState { deal: None, line: vec![] }
+    }
+
+    fn node(&self, state: &RiverSpotState) -> Node {
+        let over = match state.line.last() {
+            Some(&RiverAction::Fold) | Some(&RiverAction::Call) => true,
+            _ => state.line == vec![RiverAction::Check, RiverAction::Check],
+        };
+        match (state.deal, over) {
+            (None, _) => Node::Chance,
+            (Some(_), true) => Node::Terminal,
+            (Some(_), false) => Node::Player(state.line.len() % 2),
+        }
+    }
+
+    /// The pot counts as half each player's already.
+    fn payoff(&self, state: &RiverSpotState) -> f64 {
+        let put_in = put_in(&state.line);
+        let half_pot = self.pot as f64 / 2.0;
+        let winner = match state.line.last() {
+            Some(&RiverAction::Fold) => Some(1 - (state.line.len() - 1) % 2),
+            _ => match self.showdowns[state.deal.unwrap()] {
+                Ordering::Greater => Some(0),
+                Ordering::Less => Some(1),
+                Ordering::Equal => None,
+            },
+        };
+        match winner {
+            Some(0) => half_pot + put_in[1] as f64,
+            Some(_) => -half_pot - put_in[0] as f64,
+            None => 0.0,
+        }
+    }
+
+    fn chance_outcomes(&self, _: &RiverSpotState) -> Vec<(f64, RiverSpotState)> {
+        self.deals.iter().enumerate()
+            .map(|(deal, &(_, _, chance))| (chance, RiverSpotState { deal: Some(deal), line: vec![] }))
+            .collect()
+    }
+
+    fn actions(&self, state: &RiverSpotState) -> Vec<RiverAction> {
+        self.legal_actions(&state.line)
+    }
+
+    fn play(&self, state: &RiverSpotState, action: RiverAction) -> RiverSpotState {
+        let mut line = state.line.clone();
+        line.push(action);
+        RiverSpotState { deal: state.deal, line: line }
+    }
+
+    /// The player's combo and the line so far, like "ASKD:k,b50".
+    fn information_set(&self, state: &RiverSpotState) -> String {
+        let player = state.line.len() % 2;
+        let (first, second, _) = self.deals[state.deal.unwrap()];
+        self.information_set_of(player, if player == 0 { first } else { second }, &state.line)
+    }
+}
+
+/// An equilibrium for a river spot, as near as the iterations got.
+pub struct RiverSolution {
+    spot: RiverSpot,
+    strategy: Strategy,
+    exploitability: f64,
+}
+
+impl RiverSolution {
+    pub fn strategy(&self) -> &Strategy {
+        &self.strategy
+    }
+
+    /// How much a best response would win against the solution, in chips per hand. Zero at an equilibrium.
+    pub fn exploitability(&self) -> f64 {
+        self.exploitability
+    }
+
+    /// How often each of `player`'s combos takes each action after `line`, or nothing if it isn't their turn.
+    pub fn frequencies(&self, player: usize, line: &Vec<RiverAction>) -> Vec<((Card, Card), Vec<(RiverAction, f64)>)> {
+        if line.len() % 2 != player {
+            return vec![];
+        }
+        let actions = self.spot.legal_actions(line);
+        range(0, self.spot.combos[player].len())
+            .map(|combo| {
+                let information_set = self.spot.information_set_of(player, combo, line);
+                let probabilities = self.strategy.probabilities(&information_set, actions.len());
+                (self.spot.combos[player][combo], actions.iter().map(|&action| action).zip(probabilities.into_iter()).collect())
+            })
+            .collect()
+    }
+
+    /// What each of `player`'s combos expects to end the hand with out of the pot and the river betting,
+    /// net of what it puts in on the river.
+    pub fn evs(&self, player: usize) -> Vec<((Card, Card), f64)> {
+        let mut totals = self.spot.combos[player].iter().map(|_| (0.0, 0.0)).collect::<Vec<(f64, f64)>>();
+        for (deal, &(first, second, chance)) in self.spot.deals.iter().enumerate() {
+            let state = RiverSpotState { deal: Some(deal), line: vec![] };
+            let payoff = cfr::expected_payoff_from(&self.spot, &self.strategy, &state);
+            let (combo, payoff) = match player {
+                0 => (first, payoff),
+                _ => (second, -payoff),
+            };
+            let (value, weight) = totals[combo];
+            totals[combo] = (value + chance * (payoff + self.spot.pot as f64 / 2.0), weight + chance);
+        }
+        self.spot.combos[player].iter().zip(totals.iter())
+            .filter(|&(_, &(_, weight))| weight > 0.0)
+            .map(|(&combo, &(value, weight))| (combo, value / weight))
+            .collect()
+    }
+}
+
+/// Solves `spot` with CFR+.
+pub fn solve(spot: &RiverSpot, iterations: usize) -> RiverSolution {
+    let mut solver = Solver::new(spot.clone(), Variant::Plus);
+    solver.iterate(iterations);
+    let strategy = solver.average_strategy();
+    let exploitability = cfr::exploitability(spot, &strategy);
+    RiverSolution { spot: spot.clone(), strategy: strategy, exploitability: exploitability }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve, RiverSpot, RiverSpotErr};
+    use super::RiverAction::*;
+    use card::Value;
+    use hand::Hand;
+    use range::Range;
+
+    fn spot(first: &str, second: &str, pot: u32, stack: u32) -> RiverSpot {
+        RiverSpot::new("AS KD 8C 7H 2S".parse::<Hand>().ok().unwrap(),
+                       &first.parse::<Range>().ok().unwrap(), &second.parse::<Range>().ok().unwrap(), pot, stack).ok().unwrap()
+    }
+
+    #[test] fn bets_are_fractions_of_the_pot_up_to_all_in() {
+        assert_eq!(spot("AA", "43s", 100, 100).legal_actions(&vec![]), vec![Check, Bet(50), Bet(100)]);
+        assert_eq!(spot("AA", "43s", 100, 60).legal_actions(&vec![]), vec![Check, Bet(50), Bet(60)]);
+        assert_eq!(spot("AA", "43s", 100, 500).legal_actions(&vec![Bet(50)]), vec![Fold, Call, Bet(150), Bet(250)]);
+        assert_eq!(spot("AA", "43s", 100, 100).legal_actions(&vec![Bet(100)]), vec![Fold, Call]);
+    }
+
+    #[test] fn small_raises_go_up_to_the_minimum_raise() {
+        let deep = spot("AA", "43s", 100, 500).with_bet_sizes(vec![0.1]);
+        assert_eq!(deep.legal_actions(&vec![Bet(100)]), vec![Fold, Call, Bet(200)]);
+        assert_eq!(deep.legal_actions(&vec![Bet(100), Bet(200)]), vec![Fold, Call, Bet(300)]);
+        let short = spot("AA", "43s", 100, 150).with_bet_sizes(vec![0.1]);
+        assert_eq!(short.legal_actions(&vec![Bet(100)]), vec![Fold, Call, Bet(150)]);
+    }
+
+    #[test] fn combos_on_the_board_are_left_out() {
+        assert_eq!(spot("AA", "43s", 100, 100).combos(0).len(), 3);
+    }
+
+    #[test] fn ranges_that_cant_be_dealt_together_are_refused() {
+        let board = "AS AD AC 7H 2S".parse::<Hand>().ok().unwrap();
+        let aces = "AA".parse::<Range>().ok().unwrap();
+        let kings = "KK".parse::<Range>().ok().unwrap();
+        // Only one ace is left, so nobody can hold aces.
+        assert_eq!(RiverSpot::new(board.clone(), &aces, &kings, 100, 100).err(), Some(RiverSpotErr::NoDeals));
+        assert_eq!(RiverSpot::new(board, &kings, &Range::empty(), 100, 100).err(), Some(RiverSpotErr::NoDeals));
+    }
+
+    #[test] fn air_folds_to_a_bet_from_the_nuts() {
+        let solution = solve(&spot("AA", "43s", 100, 100), 200);
+        let facing_pot = solution.frequencies(1, &vec![Bet(100)]);
+        assert_eq!(facing_pot.len(), 4);
+        assert!(facing_pot.iter().all(|&(_, ref frequencies)| frequencies[0].0 == Fold && frequencies[0].1 > 0.95));
+    }
+
+    #[test] fn polarized_ranges_solve_to_a_small_exploitability() {
+        let solution = solve(&spot("AA, 43s", "KQo", 100, 100), 500);
+        assert!(solution.exploitability() < 1.0);
+        let evs = solution.evs(0);
+        let sets = evs.iter().filter(|&&((a, _), _)| a.value() == &Value::Ace).map(|&(_, ev)| ev).collect::<Vec<f64>>();
+        let air = evs.iter().filter(|&&((a, _), _)| a.value() != &Value::Ace).map(|&(_, ev)| ev).collect::<Vec<f64>>();
+        assert!(sets.iter().all(|&ev| ev > 99.0));
+        assert!(air.iter().all(|&ev| ev < 100.0));
+    }
+}
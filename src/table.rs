@@ -0,0 +1,774 @@
+use card::Card;
+use hand::Hand;
+use calculations::whole_deck;
+use pot::{Pot, build_pots, payouts};
+use history::{HandHistory, SeatRecord, Post, Blind, ActionRecord, ShowdownRecord};
+
+use std::cmp::min;
+use std::iter::repeat;
+use std::rand::{thread_rng, Rng};
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+    Showdown,
+}
+
+/// A player's decision. Bets and raises give the total the player's bet for the street goes up to.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Bet(u32),
+    Raise(u32),
+}
+
+/// How much a player may bet or raise.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Limit {
+    NoLimit,
+    /// Raises can be up to the size of the pot after calling.
+    PotLimit,
+    /// Bets and raises are one small bet before the turn and one big bet after, with at most `cap`
+    /// of them on each street unless only two players are left. The big blind counts as the first preflop bet.
+    FixedLimit { small_bet: u32, big_bet: u32, cap: u32 },
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum ActionErr {
+    HandOver,
+    CannotCheck,
+    NothingToCall,
+    CannotBet,
+    CannotRaise,
+    BetTooSmall,
+    BetTooLarge,
+    NotEnoughChips,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum DealErr {
+    NotEnoughPlayers,
+}
+
+/// The seats at a table and the stakes they play for. Seats with no chips sit out.
+#[derive(Debug, Clone)]
+pub struct Table {
+    stacks: Vec<u32>,
+    button: usize,
+    small_blind: u32,
+    big_blind: u32,
+    ante: u32,
+    limit: Limit,
+}
+
+impl Table {
+    pub fn new(stacks: Vec<u32>, small_blind: u32, big_blind: u32) -> Table {
+        Table { stacks: stacks, button: 0, small_blind: small_blind, big_blind: big_blind, ante: 0, limit: Limit::NoLimit }
+    }
+
+    pub fn with_ante(mut self, ante: u32) -> Table {
+        self.ante = ante;
+        self
+    }
+
+    pub fn with_limit(mut self, limit: Limit) -> Table {
+        self.limit = limit;
+        self
+    }
+
+    pub fn stacks(&self) -> &Vec<u32> {
+        &self.stacks
+    }
+
+    pub fn button(&self) -> usize {
+        self.button
+    }
+
+    pub fn deal(&self) -> Result<HandState, DealErr> {
+        let mut deck = whole_deck().cards();
+        thread_rng().shuffle(deck.as_mut_slice());
+        HandState::new(self, deck)
+    }
+
+    /// Takes the stacks from a finished hand and moves the button to the next seat with chips.
+    pub fn finish(&mut self, hand: &HandState) {
+        self.stacks = hand.stacks();
+        let seats = self.stacks.len();
+        self.button = range(1, seats + 1)
+                      .map(|offset| (self.button + offset) % seats)
+                      .find(|&seat| self.stacks[seat] > 0)
+                      .unwrap_or(self.button);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Seat {
+    stack: u32,
+    street_bet: u32,
+    total_bet: u32,
+    hole_cards: Vec<Card>,
+    dealt_in: bool,
+    folded: bool,
+    acted: bool,
+    can_raise: bool,
+}
+
+impl Seat {
+    fn is_live(&self) -> bool {
+        self.dealt_in && !self.folded
+    }
+
+    fn can_act(&self) -> bool {
+        self.is_live() && self.stack > 0
+    }
+
+    fn put_in(&mut self, amount: u32) -> u32 {
+        let amount = min(amount, self.stack);
+        self.stack -= amount;
+        self.street_bet += amount;
+        self.total_bet += amount;
+        amount
+    }
+
+    fn post_ante(&mut self, ante: u32) -> u32 {
+        let ante = min(ante, self.stack);
+        self.stack -= ante;
+        self.total_bet += ante;
+        ante
+    }
+}
+
+/// One hand of Hold'em, from the blinds to the showdown.
+#[derive(Debug, Clone)]
+pub struct HandState {
+    seats: Vec<Seat>,
+    starting_stacks: Vec<u32>,
+    button: usize,
+    small_blind: u32,
+    big_blind: u32,
+    ante: u32,
+    limit: Limit,
+    deck: Vec<Card>,
+    board: Vec<Card>,
+    street: Street,
+    to_act: Option<usize>,
+    current_bet: u32,
+    min_raise: u32,
+    bets_this_street: u32,
+    posts: Vec<Post>,
+    actions: Vec<ActionRecord>,
+    winnings: Vec<u32>,
+}
+
+impl HandState {
+    /// Posts antes and blinds and deals hole cards. Cards come off the front of `deck`.
+    /// It takes at least two seats with chips to play a hand.
+    pub fn new(table: &Table, deck: Vec<Card>) -> Result<HandState, DealErr> {
+        if table.stacks.iter().filter(|&&stack| stack > 0).count() < 2 {
+            return Err(DealErr::NotEnoughPlayers);
+        }
+        let mut deck = deck;
+        deck.reverse();
+        let mut hand = HandState {
+            seats: table.stacks.iter().map(|&stack| {
+                Seat {
+                    stack: stack,
+                    street_bet: 0,
+                    total_bet: 0,
+                    hole_cards: vec![],
+                    dealt_in: stack > 0,
+                    folded: false,
+                    acted: false,
+                    can_raise: true,
+                }
+            }).collect(),
+            starting_stacks: table.stacks.clone(),
+            button: table.button,
+            small_blind: table.small_blind,
+            big_blind: table.big_blind,
+            ante: table.ante,
+            limit: table.limit,
+            deck: deck,
+            board: vec![],
+            street: Street::Preflop,
+            to_act: None,
+            current_bet: table.big_blind,
+            min_raise: table.big_blind,
+            bets_this_street: 1,
+            posts: vec![],
+            actions: vec![],
+            winnings: repeat(0).take(table.stacks.len()).collect(),
+        };
+
+        if table.ante > 0 {
+            for (index, seat) in hand.seats.iter_mut().enumerate().filter(|&(_, ref seat)| seat.dealt_in) {
+                let ante = seat.post_ante(table.ante);
+                hand.posts.push(Post::new(index, Blind::Ante, ante));
+            }
+        }
+        hand.min_raise = hand.street_min_raise();
+        let (small_blind_seat, big_blind_seat) = hand.blind_seats();
+        let small_blind = hand.seats[small_blind_seat].put_in(table.small_blind);
+        hand.posts.push(Post::new(small_blind_seat, Blind::SmallBlind, small_blind));
+        let big_blind = hand.seats[big_blind_seat].put_in(table.big_blind);
+        hand.posts.push(Post::new(big_blind_seat, Blind::BigBlind, big_blind));
+
+        let dealing_order = hand.seats_after(hand.button).into_iter()
+                                .filter(|&seat| hand.seats[seat].dealt_in)
+                                .collect::<Vec<usize>>();
+        for _ in range(0, 2) {
+            for &seat in dealing_order.iter() {
+                let card = hand.deck.pop().unwrap();
+                hand.seats[seat].hole_cards.push(card);
+            }
+        }
+
+        hand.advance(big_blind_seat);
+        Ok(hand)
+    }
+
+    /// Heads up the button posts the small blind, otherwise the two seats after it post.
+    fn blind_seats(&self) -> (usize, usize) {
+        let dealt_in = self.seats_after(self.button).into_iter()
+                           .filter(|&seat| self.seats[seat].dealt_in)
+                           .collect::<Vec<usize>>();
+        match dealt_in.len() {
+            2 => (dealt_in[1], dealt_in[0]),
+            _ => (dealt_in[0], dealt_in[1]),
+        }
+    }
+
+    /// Every seat, starting with the one after `seat` and ending with `seat` itself.
+    fn seats_after(&self, seat: usize) -> Vec<usize> {
+        let seats = self.seats.len();
+        range(1, seats + 1).map(|offset| (seat + offset) % seats).collect()
+    }
+
+    pub fn street(&self) -> Street {
+        self.street
+    }
+
+    pub fn board(&self) -> Hand {
+        Hand::new(self.board.clone())
+    }
+
+    pub fn hole_cards(&self, seat: usize) -> Hand {
+        Hand::new(self.seats[seat].hole_cards.clone())
+    }
+
+    pub fn button(&self) -> usize {
+        self.button
+    }
+
+    pub fn to_act(&self) -> Option<usize> {
+        self.to_act
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.to_act.is_none()
+    }
+
+    pub fn pot(&self) -> u32 {
+        self.seats.iter().fold(0, |pot, seat| pot + seat.total_bet)
+    }
+
+    /// Each seat's chips, including anything won once the hand is over.
+    pub fn stacks(&self) -> Vec<u32> {
+        self.seats.iter().zip(self.winnings.iter()).map(|(seat, &won)| seat.stack + won).collect()
+    }
+
+    pub fn winnings(&self) -> &Vec<u32> {
+        &self.winnings
+    }
+
+    /// Everything done so far, after the blinds and antes.
+    pub fn actions(&self) -> &Vec<ActionRecord> {
+        &self.actions
+    }
+
+    pub fn has_folded(&self, seat: usize) -> bool {
+        self.seats[seat].folded
+    }
+
+    /// How much the player to act needs to put in to call.
+    pub fn to_call(&self) -> u32 {
+        match self.to_act {
+            Some(seat) => {
+                let seat = &self.seats[seat];
+                min(self.current_bet - min(self.current_bet, seat.street_bet), seat.stack)
+            },
+            None => 0,
+        }
+    }
+
+    /// The smallest and largest totals the player to act can bet or raise to, if they can at all.
+    pub fn raise_range(&self) -> Option<(u32, u32)> {
+        let seat = match self.to_act {
+            Some(seat) => &self.seats[seat],
+            None => return None,
+        };
+        let all_in = seat.stack + seat.street_bet;
+        if all_in <= self.current_bet || (self.current_bet > 0 && !seat.can_raise) {
+            return None;
+        }
+        let most = match self.limit {
+            Limit::NoLimit => all_in,
+            Limit::PotLimit => {
+                let to_call = self.current_bet - min(self.current_bet, seat.street_bet);
+                min(self.current_bet + self.pot() + to_call, all_in)
+            },
+            Limit::FixedLimit { cap, .. } => {
+                let heads_up = self.seats.iter().filter(|seat| seat.is_live()).count() == 2;
+                if self.bets_this_street >= cap && !heads_up {
+                    return None;
+                }
+                min(self.current_bet + self.min_raise, all_in)
+            },
+        };
+        Some((min(self.current_bet + self.min_raise, most), most))
+    }
+
+    /// The smallest full raise at the start of a street: the big blind, or the fixed bet size in limit games.
+    fn street_min_raise(&self) -> u32 {
+        match (self.limit, self.street) {
+            (Limit::FixedLimit { small_bet, .. }, Street::Preflop) => small_bet,
+            (Limit::FixedLimit { small_bet, .. }, Street::Flop) => small_bet,
+            (Limit::FixedLimit { big_bet, .. }, _) => big_bet,
+            _ => self.big_blind,
+        }
+    }
+
+    /// What the player to act may do. Bets and raises are given at their minimum size.
+    pub fn legal_actions(&self) -> Vec<Action> {
+        if self.to_act.is_none() {
+            return vec![];
+        }
+        let mut actions = match self.to_call() {
+            0 => vec![Action::Check],
+            _ => vec![Action::Fold, Action::Call],
+        };
+        match (self.raise_range(), self.current_bet) {
+            (Some((least, _)), 0) => actions.push(Action::Bet(least)),
+            (Some((least, _)), _) => actions.push(Action::Raise(least)),
+            (None, _) => (),
+        }
+        actions
+    }
+
+    pub fn act(&mut self, action: Action) -> Result<(), ActionErr> {
+        let seat = match self.to_act {
+            Some(seat) => seat,
+            None => return Err(ActionErr::HandOver),
+        };
+        let stack_before = self.seats[seat].stack;
+        match action {
+            Action::Fold => self.seats[seat].folded = true,
+            Action::Check => {
+                if self.to_call() > 0 {
+                    return Err(ActionErr::CannotCheck);
+                }
+            },
+            Action::Call => {
+                let to_call = self.to_call();
+                if to_call == 0 {
+                    return Err(ActionErr::NothingToCall);
+                }
+                self.seats[seat].put_in(to_call);
+            },
+            Action::Bet(amount) => {
+                if self.current_bet > 0 {
+                    return Err(ActionErr::CannotBet);
+                }
+                try!(self.raise_to(seat, amount));
+            },
+            Action::Raise(amount) => {
+                if self.current_bet == 0 {
+                    return Err(ActionErr::CannotRaise);
+                }
+                try!(self.raise_to(seat, amount));
+            },
+        }
+        self.seats[seat].acted = true;
+        self.seats[seat].can_raise = false;
+        let amount = stack_before - self.seats[seat].stack;
+        self.actions.push(ActionRecord::new(self.street, seat, action, amount));
+        self.advance(seat);
+        Ok(())
+    }
+
+    /// A raise smaller than the last one is only allowed all in, and doesn't reopen the betting
+    /// for players who have already acted.
+    fn raise_to(&mut self, seat: usize, amount: u32) -> Result<(), ActionErr> {
+        let (least, most) = match self.raise_range() {
+            Some(range) => range,
+            None => return Err(ActionErr::CannotRaise),
+        };
+        let all_in = self.seats[seat].stack + self.seats[seat].street_bet;
+        if amount > all_in {
+            return Err(ActionErr::NotEnoughChips);
+        }
+        if amount > most {
+            return Err(ActionErr::BetTooLarge);
+        }
+        if amount < least {
+            return Err(ActionErr::BetTooSmall);
+        }
+        let raise = amount - self.current_bet;
+        let full_raise = raise >= self.min_raise;
+        let street_bet = self.seats[seat].street_bet;
+        self.seats[seat].put_in(amount - street_bet);
+        if full_raise {
+            self.min_raise = raise;
+        }
+        self.current_bet = amount;
+        self.bets_this_street += 1;
+        for (_, other) in self.seats.iter_mut().enumerate().filter(|&(index, _)| index != seat) {
+            other.acted = false;
+            if full_raise {
+                other.can_raise = true;
+            }
+        }
+        Ok(())
+    }
+
+    fn advance(&mut self, from: usize) {
+        if self.seats.iter().filter(|seat| seat.is_live()).count() == 1 {
+            self.finish_hand();
+            return;
+        }
+        match self.next_to_act(from) {
+            Some(seat) => self.to_act = Some(seat),
+            None => self.next_street(),
+        }
+    }
+
+    fn next_to_act(&self, from: usize) -> Option<usize> {
+        let can_act = self.seats.iter().filter(|seat| seat.can_act()).count();
+        self.seats_after(from).into_iter().find(|&index| {
+            let seat = &self.seats[index];
+            let behind = seat.street_bet < self.current_bet;
+            seat.can_act() && (behind || (!seat.acted && can_act > 1))
+        })
+    }
+
+    fn next_street(&mut self) {
+        for seat in self.seats.iter_mut() {
+            seat.street_bet = 0;
+            seat.acted = false;
+            seat.can_raise = true;
+        }
+        self.current_bet = 0;
+        self.bets_this_street = 0;
+        self.street = match self.street {
+            Street::Preflop => Street::Flop,
+            Street::Flop => Street::Turn,
+            Street::Turn => Street::River,
+            _ => Street::Showdown,
+        };
+        self.min_raise = self.street_min_raise();
+        match self.street {
+            Street::Flop => self.deal_board(3),
+            Street::Turn | Street::River => self.deal_board(1),
+            _ => {
+                self.finish_hand();
+                return;
+            },
+        }
+        let button = self.button;
+        match self.next_to_act(button) {
+            Some(seat) => self.to_act = Some(seat),
+            None => self.next_street(),
+        }
+    }
+
+    fn deal_board(&mut self, cards: usize) {
+        self.deck.pop();
+        for _ in range(0, cards) {
+            let card = self.deck.pop().unwrap();
+            self.board.push(card);
+        }
+    }
+
+    /// The hands still in at the showdown, by seat.
+    pub fn showdown(&self) -> Vec<(usize, Hand)> {
+        match self.street {
+            Street::Showdown => self.seats.iter()
+                                   .enumerate()
+                                   .filter(|&(_, seat)| seat.is_live())
+                                   .map(|(index, seat)| (index, Hand::new(seat.hole_cards.clone()) + self.board()))
+                                   .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// The main pot and any side pots, from everything put in so far.
+    pub fn pots(&self) -> Vec<Pot> {
+        build_pots(&self.seats.iter().map(|seat| seat.total_bet).collect(),
+                   &self.seats.iter().map(|seat| !seat.is_live()).collect())
+    }
+
+    /// A record of the hand so far. Seats are named "Seat 1", "Seat 2" and so on.
+    pub fn history(&self) -> HandHistory {
+        HandHistory {
+            seats: self.starting_stacks.iter()
+                       .enumerate()
+                       .map(|(index, &stack)| SeatRecord::new(format!("Seat {}", index + 1), stack))
+                       .collect(),
+            button: self.button,
+            small_blind: self.small_blind,
+            big_blind: self.big_blind,
+            ante: self.ante,
+            limit: self.limit,
+            hole_cards: self.seats.iter()
+                            .map(|seat| match seat.dealt_in {
+                                true => Some(Hand::new(seat.hole_cards.clone())),
+                                false => None,
+                            })
+                            .collect(),
+            posts: self.posts.clone(),
+            actions: self.actions.clone(),
+            board: self.board.clone(),
+            showdown: self.showdown().into_iter()
+                          .map(|(seat, hand)| ShowdownRecord::new(seat, self.hole_cards(seat), &hand))
+                          .collect(),
+            winnings: self.winnings.clone(),
+        }
+    }
+
+    fn finish_hand(&mut self) {
+        self.to_act = None;
+        let showdown = self.showdown();
+        let hands = range(0, self.seats.len())
+                    .map(|seat| showdown.iter().find(|&&(index, _)| index == seat).map(|&(_, ref hand)| hand.clone()))
+                    .collect::<Vec<Option<Hand>>>();
+        let first_seat = (self.button + 1) % self.seats.len();
+        self.winnings = payouts(&self.pots(), &hands, first_seat);
+    }
+}
+
+/// A deck that deals `top` first, then the rest of the cards in order. For tests that need to know the cards.
+#[cfg(test)]
+pub fn stacked_deck(top: &str) -> Vec<Card> {
+    let top = top.parse::<Hand>().ok().unwrap();
+    let mut deck = top.cards();
+    deck.extend((whole_deck() - top).cards().into_iter());
+    deck
+}
+
+/// A heads-up hand for tests, 100 chips each: the kings in seat 0 raise, the aces call and bet the flop,
+/// and it checks down to a showdown the aces win.
+#[cfg(test)]
+pub fn played_hand() -> HandState {
+    let mut hand = HandState::new(&Table::new(vec![100, 100], 1, 2), stacked_deck("AS KD AH KC 2C 7D 8H 9C 3S JD 4C 5H")).ok().unwrap();
+    hand.act(Action::Raise(6)).ok().unwrap();
+    hand.act(Action::Call).ok().unwrap();
+    hand.act(Action::Bet(10)).ok().unwrap();
+    hand.act(Action::Call).ok().unwrap();
+    for _ in range(0, 4) {
+        hand.act(Action::Check).ok().unwrap();
+    }
+    hand
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Table, HandState, Action, ActionErr, DealErr, Street, Limit, stacked_deck};
+    use calculations::whole_deck;
+
+    fn deal(stacks: Vec<u32>) -> HandState {
+        HandState::new(&Table::new(stacks, 1, 2), whole_deck().cards()).ok().unwrap()
+    }
+
+    #[test] fn blinds_are_posted_and_the_next_seat_acts() {
+        let hand = deal(vec![100, 100, 100]);
+        assert_eq!(hand.pot(), 3);
+        assert_eq!(hand.stacks(), vec![100, 99, 98]);
+        assert_eq!(hand.to_act(), Some(0));
+    }
+
+    #[test] fn a_hand_needs_two_players_with_chips() {
+        assert_eq!(HandState::new(&Table::new(vec![100, 0], 1, 2), whole_deck().cards()).err(), Some(DealErr::NotEnoughPlayers));
+        assert_eq!(Table::new(vec![100], 1, 2).deal().err(), Some(DealErr::NotEnoughPlayers));
+    }
+
+    #[test] fn antes_go_in_the_pot() {
+        let hand = HandState::new(&Table::new(vec![100, 100, 100], 1, 2).with_ante(1), whole_deck().cards()).ok().unwrap();
+        assert_eq!(hand.pot(), 6);
+        assert_eq!(hand.to_call(), 2);
+    }
+
+    #[test] fn heads_up_the_button_posts_the_small_blind_and_acts_first() {
+        let hand = deal(vec![100, 100]);
+        assert_eq!(hand.stacks(), vec![99, 98]);
+        assert_eq!(hand.to_act(), Some(0));
+    }
+
+    #[test] fn everyone_gets_two_hole_cards() {
+        let hand = deal(vec![100, 100, 100]);
+        assert_eq!(hand.hole_cards(0).cards().len(), 2);
+        assert_eq!(hand.hole_cards(2).cards().len(), 2);
+    }
+
+    #[test] fn cannot_check_facing_a_bet() {
+        let mut hand = deal(vec![100, 100, 100]);
+        assert_eq!(hand.act(Action::Check), Err(ActionErr::CannotCheck));
+    }
+
+    #[test] fn cannot_bet_preflop() {
+        let mut hand = deal(vec![100, 100, 100]);
+        assert_eq!(hand.act(Action::Bet(6)), Err(ActionErr::CannotBet));
+    }
+
+    #[test] fn raises_must_be_at_least_the_last_raise() {
+        let mut hand = deal(vec![100, 100, 100]);
+        assert_eq!(hand.act(Action::Raise(6)), Ok(()));
+        assert_eq!(hand.act(Action::Raise(8)), Err(ActionErr::BetTooSmall));
+        assert_eq!(hand.act(Action::Raise(10)), Ok(()));
+    }
+
+    #[test] fn cannot_raise_more_than_a_stack() {
+        let mut hand = deal(vec![100, 100, 100]);
+        assert_eq!(hand.act(Action::Raise(101)), Err(ActionErr::NotEnoughChips));
+    }
+
+    #[test] fn the_big_blind_gets_an_option() {
+        let mut hand = deal(vec![100, 100, 100]);
+        hand.act(Action::Call).ok().unwrap();
+        hand.act(Action::Call).ok().unwrap();
+        assert_eq!(hand.to_act(), Some(2));
+        assert_eq!(hand.legal_actions(), vec![Action::Check, Action::Raise(4)]);
+        hand.act(Action::Check).ok().unwrap();
+        assert_eq!(hand.street(), Street::Flop);
+        assert_eq!(hand.board().cards().len(), 3);
+        assert_eq!(hand.to_act(), Some(1));
+    }
+
+    #[test] fn the_first_wager_after_the_flop_is_a_bet() {
+        let mut hand = deal(vec![100, 100, 100]);
+        hand.act(Action::Call).ok().unwrap();
+        hand.act(Action::Call).ok().unwrap();
+        hand.act(Action::Check).ok().unwrap();
+        assert_eq!(hand.legal_actions(), vec![Action::Check, Action::Bet(2)]);
+    }
+
+    #[test] fn everyone_folding_wins_the_pot() {
+        let mut hand = deal(vec![100, 100, 100]);
+        hand.act(Action::Fold).ok().unwrap();
+        hand.act(Action::Fold).ok().unwrap();
+        assert!(hand.is_over());
+        assert_eq!(hand.stacks(), vec![100, 99, 101]);
+        assert_eq!(hand.act(Action::Check), Err(ActionErr::HandOver));
+    }
+
+    #[test] fn a_short_all_in_raise_does_not_reopen_the_betting() {
+        let mut hand = deal(vec![100, 8, 100]);
+        hand.act(Action::Raise(6)).ok().unwrap();
+        hand.act(Action::Raise(8)).ok().unwrap();
+        hand.act(Action::Call).ok().unwrap();
+        assert_eq!(hand.to_act(), Some(0));
+        assert_eq!(hand.legal_actions(), vec![Action::Fold, Action::Call]);
+    }
+
+    #[test] fn all_in_players_run_the_board_out_to_a_showdown() {
+        let mut hand = HandState::new(&Table::new(vec![100, 100], 1, 2),
+                                      stacked_deck("AS KD AH KC 2C 7D 8H 9C 3S JD 4C 5H")).ok().unwrap();
+        hand.act(Action::Raise(100)).ok().unwrap();
+        hand.act(Action::Call).ok().unwrap();
+        assert!(hand.is_over());
+        assert_eq!(hand.street(), Street::Showdown);
+        assert_eq!(hand.board().cards().len(), 5);
+        assert_eq!(hand.stacks(), vec![0, 200]);
+    }
+
+    #[test] fn tied_hands_split_the_pot() {
+        let mut hand = HandState::new(&Table::new(vec![100, 100], 1, 2),
+                                      stacked_deck("2S 3D 2H 3C 4C AD KH QC 3S JD 4D 0H")).ok().unwrap();
+        hand.act(Action::Raise(100)).ok().unwrap();
+        hand.act(Action::Call).ok().unwrap();
+        assert_eq!(hand.stacks(), vec![100, 100]);
+    }
+
+    #[test] fn all_in_players_only_win_the_pots_they_are_in() {
+        let mut hand = HandState::new(&Table::new(vec![100, 30, 100], 1, 2),
+                                      stacked_deck("AS 2D 7H AH 3C 7S 2C 8D 9C KH 3S JD 4C 5H")).ok().unwrap();
+        hand.act(Action::Raise(100)).ok().unwrap();
+        hand.act(Action::Call).ok().unwrap();
+        hand.act(Action::Call).ok().unwrap();
+        assert_eq!(hand.pots().len(), 2);
+        assert_eq!(hand.stacks(), vec![140, 90, 0]);
+    }
+
+    fn deal_limit(stacks: Vec<u32>, limit: Limit) -> HandState {
+        HandState::new(&Table::new(stacks, 1, 2).with_limit(limit), whole_deck().cards()).ok().unwrap()
+    }
+
+    fn fixed_limit() -> Limit {
+        Limit::FixedLimit { small_bet: 2, big_bet: 4, cap: 4 }
+    }
+
+    #[test] fn pot_limit_raises_up_to_the_pot_after_calling() {
+        let hand = deal_limit(vec![100, 100, 100], Limit::PotLimit);
+        assert_eq!(hand.raise_range(), Some((4, 7)));
+    }
+
+    #[test] fn pot_limit_counts_earlier_raises_in_the_pot() {
+        let mut hand = deal_limit(vec![100, 100, 100], Limit::PotLimit);
+        hand.act(Action::Raise(7)).ok().unwrap();
+        assert_eq!(hand.raise_range(), Some((12, 23)));
+        assert_eq!(hand.act(Action::Raise(24)), Err(ActionErr::BetTooLarge));
+    }
+
+    #[test] fn pot_limit_raises_are_capped_by_the_stack() {
+        let hand = deal_limit(vec![5, 100, 100], Limit::PotLimit);
+        assert_eq!(hand.raise_range(), Some((4, 5)));
+    }
+
+    #[test] fn fixed_limit_raises_are_one_small_bet_before_the_turn() {
+        let mut hand = deal_limit(vec![100, 100, 100], fixed_limit());
+        assert_eq!(hand.raise_range(), Some((4, 4)));
+        assert_eq!(hand.act(Action::Raise(6)), Err(ActionErr::BetTooLarge));
+    }
+
+    #[test] fn fixed_limit_bets_are_one_big_bet_from_the_turn() {
+        let mut hand = deal_limit(vec![100, 100, 100], fixed_limit());
+        hand.act(Action::Call).ok().unwrap();
+        hand.act(Action::Call).ok().unwrap();
+        hand.act(Action::Check).ok().unwrap();
+        for _ in range(0, 3) {
+            hand.act(Action::Check).ok().unwrap();
+        }
+        assert_eq!(hand.street(), Street::Turn);
+        assert_eq!(hand.legal_actions(), vec![Action::Check, Action::Bet(4)]);
+    }
+
+    #[test] fn fixed_limit_caps_the_raises() {
+        let mut hand = deal_limit(vec![100, 100, 100], fixed_limit());
+        hand.act(Action::Raise(4)).ok().unwrap();
+        hand.act(Action::Raise(6)).ok().unwrap();
+        hand.act(Action::Raise(8)).ok().unwrap();
+        assert_eq!(hand.legal_actions(), vec![Action::Fold, Action::Call]);
+    }
+
+    #[test] fn fixed_limit_is_uncapped_heads_up() {
+        let mut hand = deal_limit(vec![100, 100], fixed_limit());
+        hand.act(Action::Raise(4)).ok().unwrap();
+        hand.act(Action::Raise(6)).ok().unwrap();
+        hand.act(Action::Raise(8)).ok().unwrap();
+        assert_eq!(hand.legal_actions(), vec![Action::Fold, Action::Call, Action::Raise(10)]);
+    }
+
+    #[test] fn finishing_a_hand_moves_the_button() {
+        let mut table = Table::new(vec![100, 100, 100], 1, 2);
+        let mut hand = table.deal().ok().unwrap();
+        hand.act(Action::Fold).ok().unwrap();
+        hand.act(Action::Fold).ok().unwrap();
+        table.finish(&hand);
+        assert_eq!(table.button(), 1);
+        assert_eq!(table.stacks(), &vec![100, 99, 101]);
+    }
+}
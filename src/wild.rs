@@ -0,0 +1,184 @@
+use card::{Card, Value, Suit};
+use card::Value::*;
+use hand::{Hand, HandRank};
+use std::cmp::Ordering;
+
+extern crate rust_combinatorics;
+use self::rust_combinatorics::combinatorics::binomial::Chooseable;
+
+static VALUES: [Value; 13] = [Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten, Jack, Queen, King, Ace];
+static SUITS: [Suit; 4] = [Suit::Diamond, Suit::Club, Suit::Heart, Suit::Spade];
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Joker {
+    /// Plays as any card at all.
+    Wild,
+    /// Plays as an ace, or as any card that completes a straight or a flush.
+    Bug,
+}
+
+/// Which cards are wild and what they're allowed to become.
+#[derive(Debug, Clone)]
+pub struct WildRules {
+    wild_values: Vec<Value>,
+    joker: Joker,
+    five_of_a_kind: bool,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Slot {
+    Natural(Card),
+    Wild,
+    Bug,
+}
+
+impl WildRules {
+    pub fn new(wild_values: Vec<Value>, joker: Joker, five_of_a_kind: bool) -> WildRules {
+        WildRules { wild_values: wild_values, joker: joker, five_of_a_kind: five_of_a_kind }
+    }
+
+    pub fn jokers_wild() -> WildRules {
+        WildRules::new(vec![], Joker::Wild, true)
+    }
+
+    pub fn deuces_wild() -> WildRules {
+        WildRules::new(vec![Two], Joker::Wild, true)
+    }
+
+    pub fn bug() -> WildRules {
+        WildRules::new(vec![], Joker::Bug, true)
+    }
+
+    /// The best five-card hand the wild cards can make, with every wild card replaced by what it plays as.
+    pub fn best_hand(&self, hand: &Hand) -> Hand {
+        let slots = hand.natural_cards().into_iter()
+            .map(|card| match self.wild_values.contains(card.value()) {
+                true => Slot::Wild,
+                false => Slot::Natural(card),
+            })
+            .chain(range(0, hand.jokers()).map(|_| match self.joker {
+                Joker::Wild => Slot::Wild,
+                Joker::Bug => Slot::Bug,
+            }))
+            .collect::<Vec<Slot>>();
+        if slots.iter().all(|slot| match *slot { Slot::Natural(_) => true, _ => false }) {
+            return hand.best_five();
+        }
+        let five_slot_sets = match slots.len() > 5 {
+            true => slots.choose(5).collect::<Vec<Vec<Slot>>>(),
+            false => vec![slots],
+        };
+        five_slot_sets.iter()
+                      .flat_map(|slots| self.substitutions(slots).into_iter())
+                      .max()
+                      .unwrap_or(Hand::new(vec![]))
+    }
+
+    pub fn compare(&self, hand: &Hand, other: &Hand) -> Ordering {
+        self.best_hand(hand).cmp(&self.best_hand(other))
+    }
+
+    fn substitutions(&self, slots: &Vec<Slot>) -> Vec<Hand> {
+        let naturals = slots.iter()
+                            .filter_map(|slot| match *slot { Slot::Natural(card) => Some(card), _ => None })
+                            .collect::<Vec<Card>>();
+        let wilds = slots.iter().filter(|slot| match **slot { Slot::Wild => true, _ => false }).count();
+        let bugs = slots.iter().filter(|slot| match **slot { Slot::Bug => true, _ => false }).count();
+
+        let mut hands = vec![];
+        // Suits only matter for flushes, so every wild card takes the same one.
+        for suit in SUITS.iter() {
+            for wild_values in value_multisets(wilds).iter() {
+                for bug_values in value_multisets(bugs).iter() {
+                    let mut cards = naturals.clone();
+                    cards.extend(wild_values.iter().chain(bug_values.iter()).map(|&value| Card::new(VALUES[value], *suit)));
+                    let hand = Hand::new(cards);
+                    let rank = hand.rank();
+                    let bugs_allowed = bug_values.iter().all(|&value| VALUES[value] == Ace) || match rank {
+                        HandRank::Straight | HandRank::Flush | HandRank::StraightFlush => true,
+                        _ => false,
+                    };
+                    if bugs_allowed && (self.five_of_a_kind || rank != HandRank::FiveOfAKind) {
+                        hands.push(hand);
+                    }
+                }
+            }
+        }
+        hands
+    }
+}
+
+/// Every way of picking `size` values, ignoring order.
+fn value_multisets(size: usize) -> Vec<Vec<usize>> {
+    match size {
+        0 => vec![vec![]],
+        _ => value_multisets(size - 1).into_iter()
+                 .flat_map(|smaller| {
+                     let lowest = *smaller.last().unwrap_or(&0);
+                     range(lowest, VALUES.len()).map(move |value| {
+                         let mut larger = smaller.clone();
+                         larger.push(value);
+                         larger
+                     })
+                 })
+                 .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WildRules, Joker};
+    use card::Value;
+    use hand::{Hand, HandRank};
+    use std::cmp::Ordering;
+
+    fn parse_hand(s: &str) -> Hand {
+        s.parse().ok().unwrap()
+    }
+
+    #[test] fn deuces_play_as_anything() {
+        let rules = WildRules::deuces_wild();
+        assert_eq!(rules.best_hand(&parse_hand("2S 2H 9D 0D JD")).rank(), HandRank::StraightFlush);
+    }
+
+    #[test] fn deuces_wild_allows_five_of_a_kind() {
+        let rules = WildRules::deuces_wild();
+        assert_eq!(rules.best_hand(&parse_hand("2S 2H AD AC AH")).rank(), HandRank::FiveOfAKind);
+    }
+
+    #[test] fn deuces_are_natural_without_wild_rules() {
+        assert_eq!(parse_hand("2S 2H 9D 0D JD").rank(), HandRank::Pair);
+    }
+
+    #[test] fn wild_cards_break_ties_by_what_they_become() {
+        let rules = WildRules::deuces_wild();
+        assert_eq!(rules.compare(&parse_hand("2S KH KD 7C 4H"), &parse_hand("QS QH QD 7D 4C")), Ordering::Greater);
+    }
+
+    #[test] fn the_bug_plays_as_an_ace() {
+        let rules = WildRules::bug();
+        let best = rules.best_hand(&parse_hand("KS KH 9D 7C XX"));
+        assert_eq!(best.rank(), HandRank::Pair);
+        assert!(best == parse_hand("KS KH 9D 7C AC"));
+    }
+
+    #[test] fn the_bug_completes_straights() {
+        let rules = WildRules::bug();
+        assert_eq!(rules.best_hand(&parse_hand("8S 9H 0D JC XX")).rank(), HandRank::Straight);
+    }
+
+    #[test] fn the_bug_makes_five_aces() {
+        let rules = WildRules::bug();
+        assert_eq!(rules.best_hand(&parse_hand("AS AH AD AC XX")).rank(), HandRank::FiveOfAKind);
+    }
+
+    #[test] fn five_of_a_kind_can_be_ruled_out() {
+        let rules = WildRules::new(vec![Value::Two], Joker::Wild, false);
+        assert_eq!(rules.best_hand(&parse_hand("2S AH AD AC AS")).rank(), HandRank::FourOfAKind);
+    }
+
+    #[test] fn wild_cards_pick_the_best_five_of_seven() {
+        let rules = WildRules::deuces_wild();
+        assert_eq!(rules.best_hand(&parse_hand("2S 3H 7D 8D 9D 0D KC")).rank(), HandRank::StraightFlush);
+    }
+}